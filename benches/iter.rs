@@ -0,0 +1,97 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Times forward and backward iteration over grapheme clusters and code points.
+
+Run with `cargo bench --bench iter`.  This uses a plain `main` rather than the unstable `#[bench]` harness so that it works on stable; under `cargo test --benches`, each case runs once as a smoke test.
+*/
+extern crate strcursor;
+
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use strcursor::StrCursor;
+
+/// Mixed-script sample: ASCII, combining marks, Hangul, CJK, emoji sequences and flags.
+const SAMPLE: &str = "The quick brown fox jumps over the lazy dog. \
+    noe\u{308}l cafe\u{301} \u{1100}\u{1161}\u{11A8} \u{D55C}\u{AD6D}\u{C5B4} \
+    \u{65E5}\u{672C}\u{8A9E} \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467} \
+    \u{1F44D}\u{1F3FD} \u{1F1E6}\u{1F1FA}\u{1F1F3}\u{1F1FF} \u{928}\u{93F}\u{937}\u{94D}\u{20}\r\n";
+
+/// Roughly 3 MB once repeated.
+const REPEAT: usize = 20_000;
+
+fn main() {
+    let benching = env::args().any(|a| a == "--bench");
+    let rounds = if benching { 10 } else { 1 };
+    let text = SAMPLE.repeat(if benching { REPEAT } else { 1 });
+
+    run("gc forward (next)", rounds, || {
+        let mut cur = StrCursor::new_at_start(&text);
+        let mut n = 0;
+        while let Some((gc, next)) = cur.next() {
+            n += gc.len();
+            cur = next;
+        }
+        n
+    });
+
+    run("gc backward (prev)", rounds, || {
+        let mut cur = StrCursor::new_at_end(&text);
+        let mut n = 0;
+        while let Some((gc, prev)) = cur.prev() {
+            n += gc.len();
+            cur = prev;
+        }
+        n
+    });
+
+    run("gc forward (iter_after)", rounds, || {
+        StrCursor::new_at_start(&text).iter_after().map(|gc| gc.len()).sum()
+    });
+
+    run("gc backward (iter_before)", rounds, || {
+        StrCursor::new_at_end(&text).iter_before().map(|gc| gc.len()).sum()
+    });
+
+    run("cp forward (next_cp)", rounds, || {
+        let mut cur = StrCursor::new_at_start(&text);
+        let mut n = 0;
+        while let Some((cp, next)) = cur.next_cp() {
+            n += cp.len_utf8();
+            cur = next;
+        }
+        n
+    });
+
+    run("cp backward (prev_cp)", rounds, || {
+        let mut cur = StrCursor::new_at_end(&text);
+        let mut n = 0;
+        while let Some((cp, prev)) = cur.prev_cp() {
+            n += cp.len_utf8();
+            cur = prev;
+        }
+        n
+    });
+}
+
+/// Runs `f` `rounds` times and reports the fastest round.
+fn run<F: FnMut() -> usize>(name: &str, rounds: usize, mut f: F) {
+    let mut best = Duration::MAX;
+    let mut bytes = 0;
+    for _ in 0..rounds {
+        let start = Instant::now();
+        bytes = black_box(f());
+        best = best.min(start.elapsed());
+    }
+    let secs = best.as_secs_f64();
+    let mb_s = if secs > 0.0 { bytes as f64 / secs / 1e6 } else { 0.0 };
+    println!("{:<28} {:>10.3} ms {:>10.1} MB/s", name, secs * 1e3, mb_s);
+}
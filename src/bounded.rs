@@ -0,0 +1,364 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Defines a cursor which cannot leave a sub-range of its string.
+*/
+use grapheme::Gc;
+use span::Span;
+use StrCursor;
+
+/**
+A cursor which is confined to a sub-range of its string.
+
+This wraps a regular `StrCursor`, but treats the bounds of a [`Span`](struct.Span.html) as though they were the start and end of the string.  Movement past either bound fails in the same way that a `StrCursor` fails at the ends of its string.  Grapheme clusters are segmented within the bounds alone, so a bound which falls inside a cluster splits it, just as slicing the string there would.  Positions reported by the cursor remain relative to the *whole* string, which makes this useful for parsing a field inside a larger document whilst still producing accurate error locations.
+
+The cursor starts at the lower bound.  Use [`into_inner`](#method.into_inner) to recover an unrestricted `StrCursor`.
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct BoundedCursor<'a> {
+    cur: StrCursor<'a>,
+    bounds: Span<'a>,
+}
+
+impl<'a> BoundedCursor<'a> {
+    /**
+    Create a new cursor bounded by two cursors, positioned at the leftmost of the two.
+
+    The cursors may be given in either order.  Returns `None` if the cursors are from different strings (even different subsets of the same string).
+    */
+    pub fn new(a: StrCursor<'a>, b: StrCursor<'a>) -> Option<BoundedCursor<'a>> {
+        Span::new(a, b).map(BoundedCursor::from_span)
+    }
+
+    /**
+    Create a new cursor bounded by a span, positioned at the start of the span.
+    */
+    pub fn from_span(span: Span<'a>) -> BoundedCursor<'a> {
+        BoundedCursor {
+            cur: span.start(),
+            bounds: span,
+        }
+    }
+
+    /**
+    Returns the bounds of this cursor.
+    */
+    #[inline]
+    pub fn bounds(&self) -> Span<'a> {
+        self.bounds
+    }
+
+    /**
+    Returns the underlying, unbounded cursor.
+    */
+    #[inline]
+    pub fn into_inner(self) -> StrCursor<'a> {
+        self.cur
+    }
+
+    /**
+    Returns a new cursor at the beginning of the previous grapheme cluster, or `None` if the cursor is currently positioned at the lower bound.
+    */
+    #[inline]
+    pub fn at_prev(self) -> Option<BoundedCursor<'a>> {
+        self.prev().map(|(_, cur)| cur)
+    }
+
+    /**
+    Returns a new cursor at the beginning of the next grapheme cluster, or `None` if the cursor is currently positioned at the upper bound.
+    */
+    #[inline]
+    pub fn at_next(self) -> Option<BoundedCursor<'a>> {
+        self.next().map(|(_, cur)| cur)
+    }
+
+    /**
+    Returns a new cursor at the beginning of the previous code point, or `None` if the cursor is currently positioned at the lower bound.
+    */
+    #[inline]
+    pub fn at_prev_cp(self) -> Option<BoundedCursor<'a>> {
+        self.prev_cp().map(|(_, cur)| cur)
+    }
+
+    /**
+    Returns a new cursor at the beginning of the next code point, or `None` if the cursor is currently positioned at the upper bound.
+    */
+    #[inline]
+    pub fn at_next_cp(self) -> Option<BoundedCursor<'a>> {
+        self.next_cp().map(|(_, cur)| cur)
+    }
+
+    /**
+    Seeks the cursor to the beginning of the previous grapheme cluster.
+
+    # Panics
+
+    If the cursor is currently at the lower bound, then this function will panic.
+    */
+    #[inline]
+    pub fn seek_prev(&mut self) {
+        *self = self.at_prev().expect("cannot seek past the lower bound");
+    }
+
+    /**
+    Seeks the cursor to the beginning of the next grapheme cluster.
+
+    # Panics
+
+    If the cursor is currently at the upper bound, then this function will panic.
+    */
+    #[inline]
+    pub fn seek_next(&mut self) {
+        *self = self.at_next().expect("cannot seek past the upper bound");
+    }
+
+    /**
+    Seeks the cursor to the beginning of the previous code point.
+
+    # Panics
+
+    If the cursor is currently at the lower bound, then this function will panic.
+    */
+    #[inline]
+    pub fn seek_prev_cp(&mut self) {
+        *self = self.at_prev_cp().expect("cannot seek past the lower bound");
+    }
+
+    /**
+    Seeks the cursor to the beginning of the next code point.
+
+    # Panics
+
+    If the cursor is currently at the upper bound, then this function will panic.
+    */
+    #[inline]
+    pub fn seek_next_cp(&mut self) {
+        *self = self.at_next_cp().expect("cannot seek past the upper bound");
+    }
+
+    /**
+    Returns both the previous grapheme cluster and the cursor having seeked before it.
+    */
+    #[inline]
+    pub fn prev(mut self) -> Option<(&'a Gc, BoundedCursor<'a>)> {
+        let (_, gc) = Gc::rsplit_from(self.slice_before())?;
+        unsafe {
+            self.cur.unsafe_set_at(gc.as_str());
+        }
+        Some((gc, self))
+    }
+
+    /**
+    Returns both the previous code point and the cursor having seeked before it.
+    */
+    #[inline]
+    pub fn prev_cp(self) -> Option<(char, BoundedCursor<'a>)> {
+        let (cp, cur) = self.cur.prev_cp()?;
+        self.restrict(cur).map(|cur| (cp, cur))
+    }
+
+    /**
+    Returns both the next grapheme cluster and the cursor having seeked past it.
+    */
+    #[inline]
+    pub fn next(mut self) -> Option<(&'a Gc, BoundedCursor<'a>)> {
+        let (gc, _) = Gc::split_from(self.slice_after())?;
+        unsafe {
+            self.cur.unsafe_seek_right(gc.len());
+        }
+        Some((gc, self))
+    }
+
+    /**
+    Returns both the next code point and the cursor having seeked past it.
+    */
+    #[inline]
+    pub fn next_cp(self) -> Option<(char, BoundedCursor<'a>)> {
+        let (cp, cur) = self.cur.next_cp()?;
+        self.restrict(cur).map(|cur| (cp, cur))
+    }
+
+    /**
+    Returns the grapheme cluster immediately to the left of the cursor, or `None` if the cursor is at the lower bound.
+    */
+    #[inline]
+    pub fn before(&self) -> Option<&'a Gc> {
+        self.prev().map(|(gc, _)| gc)
+    }
+
+    /**
+    Returns the grapheme cluster immediately to the right of the cursor, or `None` if the cursor is at the upper bound.
+    */
+    #[inline]
+    pub fn after(&self) -> Option<&'a Gc> {
+        self.next().map(|(gc, _)| gc)
+    }
+
+    /**
+    Returns the code point immediately to the left of the cursor, or `None` if the cursor is at the lower bound.
+    */
+    #[inline]
+    pub fn cp_before(&self) -> Option<char> {
+        self.prev_cp().map(|(cp, _)| cp)
+    }
+
+    /**
+    Returns the code point immediately to the right of the cursor, or `None` if the cursor is at the upper bound.
+    */
+    #[inline]
+    pub fn cp_after(&self) -> Option<char> {
+        self.next_cp().map(|(cp, _)| cp)
+    }
+
+    /**
+    Returns the contents of the string between the lower bound and the cursor.
+    */
+    #[inline]
+    pub fn slice_before(&self) -> &'a str {
+        &self.cur.s[self.bounds.start().byte_pos()..self.cur.byte_pos()]
+    }
+
+    /**
+    Returns the contents of the string between the cursor and the upper bound.
+    */
+    #[inline]
+    pub fn slice_after(&self) -> &'a str {
+        &self.cur.s[self.cur.byte_pos()..self.bounds.end().byte_pos()]
+    }
+
+    /**
+    Returns the contents of the string *between* this cursor and another cursor.
+
+    Returns `None` if the cursors are from different strings (even different subsets of the same string).
+    */
+    #[inline]
+    pub fn slice_between(&self, until: BoundedCursor<'a>) -> Option<&'a str> {
+        self.cur.slice_between(until.cur)
+    }
+
+    /**
+    Returns the contents of the string between the two bounds.
+    */
+    #[inline]
+    pub fn slice_all(&self) -> &'a str {
+        self.bounds.as_str()
+    }
+
+    /**
+    Returns the cursor's current position as the number of UTF-8 code units from the beginning of the *whole* string, not the lower bound.
+    */
+    #[inline]
+    pub fn byte_pos(&self) -> usize {
+        self.cur.byte_pos()
+    }
+
+    fn restrict(self, cur: StrCursor<'a>) -> Option<BoundedCursor<'a>> {
+        if self.bounds.contains(cur) {
+            Some(BoundedCursor { cur, bounds: self.bounds })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> From<Span<'a>> for BoundedCursor<'a> {
+    fn from(span: Span<'a>) -> BoundedCursor<'a> {
+        BoundedCursor::from_span(span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundedCursor;
+    use StrCursor;
+
+    #[test]
+    fn test_bounded_iterate() {
+        let s = "name=\"noe\u{308}l\", age=12";
+        let lo = StrCursor::new_at_left_of_byte_pos(s, 6);
+        let hi = StrCursor::new_at_left_of_byte_pos(s, 12);
+        let mut cur = BoundedCursor::new(hi, lo).unwrap();
+        assert_eq!(cur.slice_all(), "noe\u{308}l");
+        assert_eq!(cur.byte_pos(), 6);
+        assert_eq!(cur.before(), None);
+        assert_eq!(cur.at_prev(), None);
+        assert_eq!(cur.cp_before(), None);
+        assert_eq!(cur.slice_after(), "noe\u{308}l");
+
+        let mut seen = vec![];
+        while let Some((gc, next)) = cur.next() {
+            seen.push((gc.as_str(), cur.byte_pos()));
+            cur = next;
+        }
+        assert_eq!(seen, vec![("n", 6), ("o", 7), ("e\u{308}", 8), ("l", 11)]);
+        assert_eq!(cur.byte_pos(), 12);
+        assert_eq!(cur.after(), None);
+        assert_eq!(cur.cp_after(), None);
+        assert_eq!(cur.slice_after(), "");
+        assert_eq!(cur.slice_before(), "noe\u{308}l");
+        assert_eq!(cur.into_inner().after().map(|gc| gc.as_str()), Some("\""));
+
+        cur.seek_prev();
+        cur.seek_prev_cp();
+        assert_eq!(cur.byte_pos(), 9);
+        assert_eq!(cur.cp_after(), Some('\u{308}'));
+        assert_eq!(cur.bounds().byte_range(), 6..12);
+    }
+
+    #[test]
+    fn test_bounded_from_span() {
+        let s = "[abc]";
+        let span = StrCursor::new_at_left_of_byte_pos(s, 1)
+            .span_to(StrCursor::new_at_left_of_byte_pos(s, 4)).unwrap();
+        let cur = BoundedCursor::from(span);
+        assert_eq!(cur, BoundedCursor::from_span(span));
+        assert_eq!(cur.bounds(), span);
+        assert_eq!(cur.at_next_cp().and_then(|c| c.at_next()).map(|c| c.byte_pos()), Some(3));
+        assert_eq!(BoundedCursor::new(span.start(), StrCursor::new_at_end(&s[1..])), None);
+    }
+
+    #[test]
+    fn test_bounded_mid_cluster() {
+        let s = "xe\u{308}y";
+        let lo = StrCursor::new_at_left_of_byte_pos(s, 1);
+        let mid = StrCursor::new_at_cp_left_of_byte_pos(s, 2);
+        let cur = BoundedCursor::new(lo, mid).unwrap();
+        assert_eq!(cur.slice_after(), "e");
+        assert_eq!(cur.after().map(|gc| gc.as_str()), Some("e"));
+        let end = cur.at_next().unwrap();
+        assert_eq!(end.byte_pos(), 2);
+        assert_eq!(end.after(), None);
+        assert_eq!(end.before().map(|gc| gc.as_str()), Some("e"));
+        assert_eq!(end.at_prev(), Some(cur));
+
+        let end = StrCursor::new_at_end(s);
+        let mut cur = BoundedCursor::new(mid, end).unwrap();
+        assert_eq!(cur.after().map(|gc| gc.as_str()), Some("\u{308}"));
+        cur.seek_next();
+        cur.seek_next();
+        assert_eq!(cur.after(), None);
+        assert_eq!(cur.before().map(|gc| gc.as_str()), Some("y"));
+        cur.seek_prev();
+        assert_eq!(cur.before().map(|gc| gc.as_str()), Some("\u{308}"));
+        cur.seek_prev();
+        assert_eq!(cur.byte_pos(), 2);
+        assert_eq!(cur.at_prev(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bounded_seek_past_bound() {
+        let s = "abc";
+        let mut cur = BoundedCursor::new(StrCursor::new_at_start(s),
+            StrCursor::new_at_left_of_byte_pos(s, 1)).unwrap();
+        cur.seek_next();
+        cur.seek_next();
+    }
+}
@@ -0,0 +1,125 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Grapheme cluster boundary arithmetic on byte offsets.
+
+These are the grapheme cluster equivalents of `str::is_char_boundary`, for use when a byte offset is all that is needed, rather than a full [`StrCursor`](../struct.StrCursor.html).
+
+All functions panic if the given position is greater than the length of the string, but are otherwise total: positions which do not lie on a code point boundary are simply not grapheme cluster boundaries.
+*/
+use uniseg::GraphemeCursor;
+
+/**
+Is `pos` a grapheme cluster boundary in `s`?
+
+The start and end of the string are always boundaries.
+
+# Panics
+
+If `pos` is greater than `s.len()`, then this function will panic.
+*/
+pub fn is_gc_boundary(s: &str, pos: usize) -> bool {
+    check_pos(s, pos);
+    if !s.is_char_boundary(pos) {
+        return false;
+    }
+    GraphemeCursor::new(pos, s.len(), /*is_extended:*/true)
+        .is_boundary(s, 0)
+        .unwrap_or_else(|_| unsafe { debug_unreachable!() })
+}
+
+/**
+Returns the largest grapheme cluster boundary in `s` which is less than or equal to `pos`.
+
+# Panics
+
+If `pos` is greater than `s.len()`, then this function will panic.
+*/
+pub fn prev_gc_boundary(s: &str, pos: usize) -> usize {
+    let pos = floor_char_boundary(s, pos);
+    if is_gc_boundary(s, pos) {
+        return pos;
+    }
+    GraphemeCursor::new(pos, s.len(), /*is_extended:*/true)
+        .prev_boundary(s, 0)
+        .unwrap_or_else(|_| unsafe { debug_unreachable!() })
+        .unwrap_or(0)
+}
+
+/**
+Returns the smallest grapheme cluster boundary in `s` which is greater than or equal to `pos`.
+
+# Panics
+
+If `pos` is greater than `s.len()`, then this function will panic.
+*/
+pub fn next_gc_boundary(s: &str, pos: usize) -> usize {
+    if is_gc_boundary(s, pos) {
+        return pos;
+    }
+    let pos = floor_char_boundary(s, pos);
+    GraphemeCursor::new(pos, s.len(), /*is_extended:*/true)
+        .next_boundary(s, 0)
+        .unwrap_or_else(|_| unsafe { debug_unreachable!() })
+        .unwrap_or(s.len())
+}
+
+fn floor_char_boundary(s: &str, mut pos: usize) -> usize {
+    check_pos(s, pos);
+    while !s.is_char_boundary(pos) {
+        pos -= 1;
+    }
+    pos
+}
+
+fn check_pos(s: &str, pos: usize) {
+    if s.len() < pos {
+        panic!("byte position out of bounds: the len is {} but the position is {}",
+            s.len(), pos);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::{is_gc_boundary, next_gc_boundary, prev_gc_boundary};
+    use uniseg::UnicodeSegmentation as UniSeg;
+
+    const FIXTURES: &[&str] = &[
+        "",
+        "Jäger,Jäger,大嫌い,💪❤!",
+        "noe\u{308}l",
+        "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}x\u{1F1E6}\u{1F1FA}\u{1F1F3}\u{1F1FF}\r\n",
+        "\u{308}a\u{1100}\u{1161}\u{11A8}",
+    ];
+
+    #[test]
+    fn test_bounds_match_grapheme_indices() {
+        for s in FIXTURES {
+            let mut expected: Vec<usize> = UniSeg::grapheme_indices(*s, /*is_extended:*/true)
+                .map(|(i, _)| i).collect();
+            expected.push(s.len());
+            for pos in 0..s.len() + 1 {
+                assert_eq!(is_gc_boundary(s, pos), expected.contains(&pos), "{:?} @ {}", s, pos);
+                let prev = *expected.iter().rev().find(|&&b| b <= pos).unwrap();
+                let next = *expected.iter().find(|&&b| b >= pos).unwrap();
+                assert_eq!(prev_gc_boundary(s, pos), prev, "{:?} @ {}", s, pos);
+                assert_eq!(next_gc_boundary(s, pos), next, "{:?} @ {}", s, pos);
+            }
+            assert!(is_gc_boundary(s, s.len()));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bounds_out_of_range() {
+        is_gc_boundary("abc", 4);
+    }
+}
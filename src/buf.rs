@@ -0,0 +1,781 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Defines an owned cursor type, and a set of positions which track edits made through it.
+*/
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use grapheme::{Gc, GcBuf};
+use iter::{IterAfter, IterBefore};
+use StrCursor;
+
+/**
+An owned equivalent of [`StrCursor`](struct.StrCursor.html).
+
+This owns a `String` along with a position inside it, so that a "string with a position" can be stored in a structure or returned from a function without borrowing.  Each operation is carried out by creating a transient `StrCursor` over the owned string; use [`as_cursor`](#method.as_cursor) to access the full borrowed API.
+
+The same guarantees apply as for `StrCursor`: the position always lies on a code point boundary within the string, and on a grapheme cluster boundary unless the code point-specific methods are used.
+
+Unlike `StrCursor`, equality compares the *contents* of the string, rather than its identity.  Cloning a `StrCursorBuf` clones the string.
+
+Requires the `alloc` feature.
+*/
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct StrCursorBuf {
+    s: String,
+    at: usize,
+}
+
+impl StrCursorBuf {
+    /**
+    Create a new cursor at the start of `s`.
+    */
+    pub fn new_at_start(s: String) -> StrCursorBuf {
+        StrCursorBuf {
+            s,
+            at: 0,
+        }
+    }
+
+    /**
+    Create a new cursor past at the end of `s`.
+    */
+    pub fn new_at_end(s: String) -> StrCursorBuf {
+        let at = s.len();
+        StrCursorBuf {
+            s,
+            at,
+        }
+    }
+
+    /**
+    Create a new cursor at the first grapheme cluster which begins at or to the left of the given byte position.
+    */
+    pub fn new_at_left_of_byte_pos(s: String, byte_pos: usize) -> StrCursorBuf {
+        let at = StrCursor::new_at_left_of_byte_pos(&s, byte_pos).byte_pos();
+        StrCursorBuf {
+            s,
+            at,
+        }
+    }
+
+    /**
+    Create a new cursor at the first grapheme cluster which begins at or to the right of the given byte position.
+    */
+    pub fn new_at_right_of_byte_pos(s: String, byte_pos: usize) -> StrCursorBuf {
+        let at = StrCursor::new_at_right_of_byte_pos(&s, byte_pos).byte_pos();
+        StrCursorBuf {
+            s,
+            at,
+        }
+    }
+
+    /**
+    Returns a borrowed cursor at the same position.
+    */
+    #[inline]
+    pub fn as_cursor(&self) -> StrCursor<'_> {
+        unsafe {
+            let mut cur = StrCursor::new_at_start(&self.s);
+            cur.unsafe_seek_right(self.at);
+            cur
+        }
+    }
+
+    /**
+    Consumes the cursor, returning the underlying string.
+    */
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.s
+    }
+
+    /**
+    Returns a new cursor at the beginning of the previous grapheme cluster, or `None` if the cursor is currently positioned at the beginning of the string.
+    */
+    #[inline]
+    pub fn at_prev(self) -> Option<StrCursorBuf> {
+        self.at_with(|cur| cur.at_prev())
+    }
+
+    /**
+    Returns a new cursor at the beginning of the next grapheme cluster, or `None` if the cursor is currently positioned at the end of the string.
+    */
+    #[inline]
+    pub fn at_next(self) -> Option<StrCursorBuf> {
+        self.at_with(|cur| cur.at_next())
+    }
+
+    /**
+    Returns a new cursor at the beginning of the previous code point, or `None` if the cursor is currently positioned at the beginning of the string.
+
+    # Note
+
+    Where possible, you should prefer `at_prev`.
+    */
+    #[inline]
+    pub fn at_prev_cp(self) -> Option<StrCursorBuf> {
+        self.at_with(|cur| cur.at_prev_cp())
+    }
+
+    /**
+    Returns a new cursor at the beginning of the next code point, or `None` if the cursor is currently positioned at the end of the string.
+
+    # Note
+
+    Where possible, you should prefer `at_next`.
+    */
+    #[inline]
+    pub fn at_next_cp(self) -> Option<StrCursorBuf> {
+        self.at_with(|cur| cur.at_next_cp())
+    }
+
+    /**
+    Seeks the cursor to the beginning of the previous grapheme cluster.
+
+    # Panics
+
+    If the cursor is currently at the start of the string, then this function will panic.
+    */
+    #[inline]
+    pub fn seek_prev(&mut self) {
+        if !self.try_seek_with(|cur| cur.at_prev()) {
+            panic!("cannot seek past the beginning of a string");
+        }
+    }
+
+    /**
+    Seeks the cursor to the beginning of the next grapheme cluster.
+
+    # Panics
+
+    If the cursor is currently at the end of the string, then this function will panic.
+    */
+    #[inline]
+    pub fn seek_next(&mut self) {
+        if !self.try_seek_with(|cur| cur.at_next()) {
+            panic!("cannot seek past the end of a string");
+        }
+    }
+
+    /**
+    Seeks the cursor to the beginning of the previous code point.
+
+    # Panics
+
+    If the cursor is currently at the start of the string, then this function will panic.
+
+    # Note
+
+    Where possible, you should prefer `seek_prev`.
+    */
+    #[inline]
+    pub fn seek_prev_cp(&mut self) {
+        if !self.try_seek_with(|cur| cur.at_prev_cp()) {
+            panic!("cannot seek past the beginning of a string");
+        }
+    }
+
+    /**
+    Seeks the cursor to the beginning of the next code point.
+
+    # Panics
+
+    If the cursor is currently at the end of the string, then this function will panic.
+
+    # Note
+
+    Where possible, you should prefer `seek_next`.
+    */
+    #[inline]
+    pub fn seek_next_cp(&mut self) {
+        if !self.try_seek_with(|cur| cur.at_next_cp()) {
+            panic!("cannot seek past the end of a string");
+        }
+    }
+
+    /**
+    Returns the grapheme cluster immediately to the left of the cursor, or `None` is the cursor is at the start of the string.
+    */
+    #[inline]
+    pub fn before(&self) -> Option<&Gc> {
+        self.as_cursor().before()
+    }
+
+    /**
+    Returns the grapheme cluster immediately to the right of the cursor, or `None` is the cursor is at the end of the string.
+    */
+    #[inline]
+    pub fn after(&self) -> Option<&Gc> {
+        self.as_cursor().after()
+    }
+
+    /**
+    Returns the code point immediately to the left of the cursor, or `None` is the cursor is at the start of the string.
+    */
+    #[inline]
+    pub fn cp_before(&self) -> Option<char> {
+        self.as_cursor().cp_before()
+    }
+
+    /**
+    Returns the code point immediately to the right of the cursor, or `None` is the cursor is at the end of the string.
+    */
+    #[inline]
+    pub fn cp_after(&self) -> Option<char> {
+        self.as_cursor().cp_after()
+    }
+
+    /**
+    Returns the contents of the string to the left of the cursor.
+    */
+    #[inline]
+    pub fn slice_before(&self) -> &str {
+        &self.s[..self.at]
+    }
+
+    /**
+    Returns the contents of the string to the right of the cursor.
+    */
+    #[inline]
+    pub fn slice_after(&self) -> &str {
+        &self.s[self.at..]
+    }
+
+    /**
+    Returns the entire string behind the cursor.
+    */
+    #[inline]
+    pub fn slice_all(&self) -> &str {
+        &self.s
+    }
+
+    /**
+    Returns an iterator over the grapheme clusters to the right of the cursor.
+    */
+    #[inline]
+    pub fn iter_after(&self) -> IterAfter<'_> {
+        self.as_cursor().iter_after()
+    }
+
+    /**
+    Returns an iterator over the grapheme clusters to the left of the cursor, moving *leftward*.
+    */
+    #[inline]
+    pub fn iter_before(&self) -> IterBefore<'_> {
+        self.as_cursor().iter_before()
+    }
+
+    /**
+    Returns the cursor's current position within the string as the number of UTF-8 code units from the beginning of the string.
+    */
+    #[inline]
+    pub fn byte_pos(&self) -> usize {
+        self.at
+    }
+
+    /**
+    Inserts a string at the cursor, leaving the cursor after the inserted text.
+
+    If the inserted text combines with the text after it into a single grapheme cluster (for example, inserting a base character immediately before a combining mark), the cursor is moved right to the end of that cluster, so that it always remains on a grapheme cluster boundary.  Text which combines with the text *before* it (such as inserting a combining mark immediately after a base character) does not require any adjustment.
+    */
+    pub fn insert_str(&mut self, s: &str) {
+        self.s.insert_str(self.at, s);
+        self.at = StrCursor::new_at_right_of_byte_pos(&self.s, self.at + s.len()).byte_pos();
+    }
+
+    /**
+    Inserts a grapheme cluster at the cursor, leaving the cursor after the inserted text.
+
+    See [`insert_str`](#method.insert_str) for how the cursor is positioned if the cluster combines with the surrounding text.
+    */
+    #[inline]
+    pub fn insert_gc(&mut self, gc: &Gc) {
+        self.insert_str(gc.as_str())
+    }
+
+    /**
+    Inserts a code point at the cursor, leaving the cursor after the inserted text.
+
+    See [`insert_str`](#method.insert_str) for how the cursor is positioned if the code point combines with the surrounding text.
+    */
+    #[inline]
+    pub fn insert_char(&mut self, c: char) {
+        let mut buf = [0; 4];
+        self.insert_str(c.encode_utf8(&mut buf))
+    }
+
+    /**
+    Removes and returns the grapheme cluster immediately to the left of the cursor, or `None` if the cursor is at the start of the string.
+
+    This is the equivalent of pressing backspace: the whole cluster is removed, regardless of how many code points it contains.  If removing the cluster causes the text on either side to combine, the cursor is moved left to the nearest grapheme cluster boundary.
+    */
+    pub fn delete_prev(&mut self) -> Option<GcBuf> {
+        let (gc, start) = {
+            let gc = self.before()?;
+            (GcBuf::from(gc), self.at - gc.len())
+        };
+        self.remove(start, self.at);
+        Some(gc)
+    }
+
+    /**
+    Removes and returns the grapheme cluster immediately to the right of the cursor, or `None` if the cursor is at the end of the string.
+
+    This is the equivalent of pressing delete: the whole cluster is removed, regardless of how many code points it contains.  If removing the cluster causes the text on either side to combine, the cursor is moved left to the nearest grapheme cluster boundary.
+    */
+    pub fn delete_next(&mut self) -> Option<GcBuf> {
+        let (gc, end) = {
+            let gc = self.after()?;
+            (GcBuf::from(gc), self.at + gc.len())
+        };
+        self.remove(self.at, end);
+        Some(gc)
+    }
+
+    fn remove(&mut self, start: usize, end: usize) {
+        self.s.drain(start..end);
+        self.at = StrCursor::new_at_left_of_byte_pos(&self.s, start).byte_pos();
+    }
+
+    fn at_with<F>(mut self, f: F) -> Option<StrCursorBuf>
+    where F: FnOnce(StrCursor) -> Option<StrCursor> {
+        if self.try_seek_with(f) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn try_seek_with<F>(&mut self, f: F) -> bool
+    where F: FnOnce(StrCursor) -> Option<StrCursor> {
+        match f(self.as_cursor()).map(|cur| cur.byte_pos()) {
+            Some(at) => {
+                self.at = at;
+                true
+            },
+            None => false
+        }
+    }
+}
+
+impl<'a> From<StrCursor<'a>> for StrCursorBuf {
+    fn from(cur: StrCursor<'a>) -> StrCursorBuf {
+        StrCursorBuf {
+            s: cur.slice_all().to_owned(),
+            at: cur.byte_pos(),
+        }
+    }
+}
+
+impl From<StrCursorBuf> for String {
+    fn from(cur: StrCursorBuf) -> String {
+        cur.into_string()
+    }
+}
+
+/**
+A handle to a position tracked by a [`PositionSet`](struct.PositionSet.html).
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct CursorPos(pub(crate) usize);
+
+/**
+An owned cursor, together with a set of additional remembered positions which are kept up to date as the text is edited.
+
+This is intended for things like marks and selection endpoints in an editor.  All edits must be made through the set itself, so it is not possible to modify the text without adjusting the tracked positions.  After each edit:
+
+* positions after the edit point are shifted by the number of bytes inserted or removed,
+* positions inside a deleted region collapse to the start of that region, and
+* every position is moved to a grapheme cluster boundary, in case the edit caused clusters to merge or split.  As with [`StrCursorBuf`](struct.StrCursorBuf.html), positions are moved right after an insertion, and left after a deletion.
+
+A tracked position exactly at the point of an insertion stays *before* the inserted text, whilst the caret moves after it.
+
+Requires the `alloc` feature.
+*/
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionSet {
+    text: StrCursorBuf,
+    positions: Vec<usize>,
+}
+
+impl PositionSet {
+    /**
+    Create a new set with no tracked positions.  The caret is the position of `text`.
+    */
+    pub fn new(text: StrCursorBuf) -> PositionSet {
+        PositionSet {
+            text,
+            positions: vec![],
+        }
+    }
+
+    /**
+    Returns the text and caret.
+    */
+    #[inline]
+    pub fn text(&self) -> &StrCursorBuf {
+        &self.text
+    }
+
+    /**
+    Consumes the set, returning the text and caret.
+    */
+    #[inline]
+    pub fn into_text(self) -> StrCursorBuf {
+        self.text
+    }
+
+    /**
+    Starts tracking the grapheme cluster boundary at or to the left of the given byte position.
+
+    # Panics
+
+    If `byte_pos` is outside of the text, then this function will panic.
+    */
+    pub fn track(&mut self, byte_pos: usize) -> CursorPos {
+        let at = StrCursor::new_at_left_of_byte_pos(&self.text.s, byte_pos).byte_pos();
+        self.positions.push(at);
+        CursorPos(self.positions.len() - 1)
+    }
+
+    /**
+    Starts tracking the current position of the caret.
+    */
+    pub fn track_caret(&mut self) -> CursorPos {
+        let at = self.text.at;
+        self.positions.push(at);
+        CursorPos(self.positions.len() - 1)
+    }
+
+    /**
+    Returns a cursor at a tracked position.
+
+    # Panics
+
+    If `pos` was not produced by this set, then this function may panic.
+    */
+    pub fn get(&self, pos: CursorPos) -> StrCursor<'_> {
+        unsafe {
+            let mut cur = StrCursor::new_at_start(&self.text.s);
+            cur.unsafe_seek_right(self.positions[pos.0]);
+            cur
+        }
+    }
+
+    /**
+    Moves the caret to a tracked position.
+    */
+    pub fn move_caret_to(&mut self, pos: CursorPos) {
+        self.text.at = self.positions[pos.0];
+    }
+
+    /**
+    Moves the caret using the given function, returning `false` (and leaving the caret unchanged) if it returns `None`.
+
+    For example, `set.move_caret(|cur| cur.at_next())`.
+    */
+    pub fn move_caret<F>(&mut self, f: F) -> bool
+    where F: FnOnce(StrCursor) -> Option<StrCursor> {
+        self.text.try_seek_with(f)
+    }
+
+    /**
+    Inserts a string at the caret, leaving the caret after the inserted text and adjusting all tracked positions.
+    */
+    pub fn insert_str(&mut self, s: &str) {
+        let at = self.text.at;
+        self.text.insert_str(s);
+        let text = &self.text.s;
+        for pos in &mut self.positions {
+            if *pos > at {
+                *pos += s.len();
+            }
+            *pos = StrCursor::new_at_right_of_byte_pos(text, *pos).byte_pos();
+        }
+    }
+
+    /**
+    Inserts a grapheme cluster at the caret, leaving the caret after the inserted text and adjusting all tracked positions.
+    */
+    #[inline]
+    pub fn insert_gc(&mut self, gc: &Gc) {
+        self.insert_str(gc.as_str())
+    }
+
+    /**
+    Inserts a code point at the caret, leaving the caret after the inserted text and adjusting all tracked positions.
+    */
+    #[inline]
+    pub fn insert_char(&mut self, c: char) {
+        let mut buf = [0; 4];
+        self.insert_str(c.encode_utf8(&mut buf))
+    }
+
+    /**
+    Removes and returns the grapheme cluster immediately to the left of the caret, adjusting all tracked positions.  Returns `None` if the caret is at the start of the text.
+    */
+    pub fn delete_prev(&mut self) -> Option<GcBuf> {
+        let end = self.text.at;
+        let gc = self.text.delete_prev()?;
+        self.adjust_for_delete(end - gc.len(), end);
+        Some(gc)
+    }
+
+    /**
+    Removes and returns the grapheme cluster immediately to the right of the caret, adjusting all tracked positions.  Returns `None` if the caret is at the end of the text.
+    */
+    pub fn delete_next(&mut self) -> Option<GcBuf> {
+        let start = self.text.at;
+        let gc = self.text.delete_next()?;
+        self.adjust_for_delete(start, start + gc.len());
+        Some(gc)
+    }
+
+    /**
+    Removes and returns the text between the caret and a tracked position, adjusting all tracked positions.  The caret is left at the start of the removed region.
+    */
+    pub fn delete_to(&mut self, pos: CursorPos) -> String {
+        let (a, b) = (self.text.at, self.positions[pos.0]);
+        let (start, end) = if a <= b { (a, b) } else { (b, a) };
+        let removed = self.text.s[start..end].to_owned();
+        self.text.remove(start, end);
+        self.adjust_for_delete(start, end);
+        removed
+    }
+
+    fn adjust_for_delete(&mut self, start: usize, end: usize) {
+        let text = &self.text.s;
+        for pos in &mut self.positions {
+            if *pos >= end {
+                *pos -= end - start;
+            } else if *pos > start {
+                *pos = start;
+            }
+            *pos = StrCursor::new_at_left_of_byte_pos(text, *pos).byte_pos();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+    use std::string::String;
+    use std::vec::Vec;
+    use super::{PositionSet, StrCursorBuf};
+    use StrCursor;
+
+    #[test]
+    fn test_new() {
+        let s = "they hit, fight, kick";
+        let cur = StrCursorBuf::new_at_start(s.into());
+        assert_eq!((cur.byte_pos(), cur.slice_before(), cur.slice_after()), (0, "", s));
+        let cur = StrCursorBuf::new_at_end(s.into());
+        assert_eq!((cur.byte_pos(), cur.slice_before(), cur.slice_after()), (21, s, ""));
+        let cur = StrCursorBuf::new_at_left_of_byte_pos("noe\u{308}l".into(), 4);
+        assert_eq!(cur.byte_pos(), 2);
+        let cur = StrCursorBuf::new_at_right_of_byte_pos("noe\u{308}l".into(), 4);
+        assert_eq!(cur.byte_pos(), 5);
+    }
+
+    #[test]
+    fn test_iter_next() {
+        let s = "a\u{308}b💪\r\n";
+        let mut cur = StrCursorBuf::new_at_start(s.into());
+        let mut seen = vec![];
+        while let Some(gc) = cur.after().map(|gc| gc.as_str().to_owned()) {
+            seen.push(gc);
+            cur.seek_next();
+        }
+        assert_eq!(seen, vec!["a\u{308}", "b", "💪", "\r\n"]);
+        assert_eq!(cur.after(), None);
+        assert_eq!(cur.clone().at_next(), None);
+
+        let mut seen = vec![];
+        while let Some(gc) = cur.before().map(|gc| gc.as_str().to_owned()) {
+            seen.push(gc);
+            cur.seek_prev();
+        }
+        assert_eq!(seen, vec!["\r\n", "💪", "b", "a\u{308}"]);
+        assert_eq!(cur.before(), None);
+        assert_eq!(cur.clone().at_prev(), None);
+    }
+
+    #[test]
+    fn test_iter_after_before() {
+        let s = "a\u{308}b\u{1F4AA}\r\n";
+        let cur = StrCursorBuf::new_at_start(s.into()).at_next().unwrap().at_next().unwrap();
+        let after: Vec<_> = cur.iter_after().map(|gc| gc.as_str()).collect();
+        assert_eq!(after, vec!["💪", "\r\n"]);
+        let before: Vec<_> = cur.iter_before().map(|gc| gc.as_str()).collect();
+        assert_eq!(before, vec!["b", "a\u{308}"]);
+        assert_eq!(cur.byte_pos(), 4);
+    }
+
+    #[test]
+    fn test_iter_cp() {
+        let s = "a\u{308}b";
+        let cur = StrCursorBuf::new_at_start(s.into());
+        let cur = cur.at_next_cp().unwrap();
+        assert_eq!((cur.cp_before(), cur.cp_after()), (Some('a'), Some('\u{308}')));
+        let mut cur = cur.at_next_cp().unwrap();
+        cur.seek_next_cp();
+        assert_eq!(cur.clone().at_next_cp(), None);
+        cur.seek_prev_cp();
+        assert_eq!(cur.byte_pos(), 3);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut cur = StrCursorBuf::new_at_start("hit".into());
+        cur.insert_str("they ");
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("they ", "hit"));
+        cur.seek_next();
+        cur.insert_char('e');
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("they he", "it"));
+        cur.insert_gc(::Gc::from_str("\u{308}").unwrap());
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("they he\u{308}", "it"));
+        assert_eq!(cur.before().map(|gc| gc.as_str()), Some("e\u{308}"));
+        cur.insert_str("");
+        assert_eq!(cur.byte_pos(), 9);
+        cur.seek_next();
+        cur.seek_next();
+        cur.insert_str("!");
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("they he\u{308}it!", ""));
+    }
+
+    #[test]
+    fn test_insert_merges_after() {
+        // Inserting a base before an existing mark absorbs the mark; the cursor must stay on a boundary.
+        let mut cur = StrCursorBuf::new_at_start("\u{308}x".into());
+        cur.insert_char('a');
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("a\u{308}", "x"));
+        assert_eq!(cur.as_cursor(), StrCursor::new_at_left_of_byte_pos(cur.slice_all(), 3));
+    }
+
+    #[test]
+    fn test_delete() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let mut cur = StrCursorBuf::new_at_end(format!("a{}b\u{308}", family));
+        assert_eq!(cur.delete_next(), None);
+        assert_eq!(cur.delete_prev().as_ref().map(|gc| gc.as_str()), Some("b\u{308}"));
+        assert_eq!(cur.delete_prev().as_ref().map(|gc| gc.as_str()), Some(family));
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("a", ""));
+        cur.insert_str("xyz");
+        cur.seek_prev();
+        cur.seek_prev();
+        assert_eq!(cur.delete_next().as_ref().map(|gc| gc.as_str()), Some("y"));
+        assert_eq!((cur.slice_before(), cur.slice_after()), ("ax", "z"));
+        assert_eq!(cur.delete_prev().as_ref().map(|gc| gc.as_str()), Some("x"));
+        assert_eq!(cur.delete_prev().as_ref().map(|gc| gc.as_str()), Some("a"));
+        assert_eq!(cur.delete_prev(), None);
+        assert_eq!(cur.into_string(), "z");
+    }
+
+    #[test]
+    fn test_delete_merges() {
+        // Removing the "a" leaves a Hangul leading and vowel jamo next to each other, which form a single cluster.
+        let mut cur = StrCursorBuf::new_at_left_of_byte_pos("\u{1100}a\u{1161}".into(), 3);
+        assert_eq!(cur.delete_next().as_ref().map(|gc| gc.as_str()), Some("a"));
+        assert_eq!((cur.byte_pos(), cur.slice_after()), (0, "\u{1100}\u{1161}"));
+
+        let mut cur = StrCursorBuf::new_at_left_of_byte_pos("\u{1100}a\u{1161}".into(), 4);
+        assert_eq!(cur.delete_prev().as_ref().map(|gc| gc.as_str()), Some("a"));
+        assert_eq!((cur.byte_pos(), cur.slice_after()), (0, "\u{1100}\u{1161}"));
+    }
+
+    #[test]
+    fn test_borrowed_round_trip() {
+        let s = String::from("they hit, fight, kick");
+        let cur = StrCursor::new_at_left_of_byte_pos(&s, 5);
+        let buf = StrCursorBuf::from(cur);
+        drop(s);
+        assert_eq!(buf.slice_after(), "hit, fight, kick");
+        assert_eq!(buf.as_cursor().byte_pos(), 5);
+        assert_eq!(buf.as_cursor().slice_all(), buf.slice_all());
+        let other = buf.clone();
+        assert_eq!(buf, other);
+        assert!(buf.slice_all().as_ptr() != other.slice_all().as_ptr());
+        assert_eq!(String::from(buf), "they hit, fight, kick");
+    }
+
+    fn position_set(s: &str, caret: usize) -> PositionSet {
+        PositionSet::new(StrCursorBuf::new_at_left_of_byte_pos(s.into(), caret))
+    }
+
+    #[test]
+    fn test_position_set_insert() {
+        let mut set = position_set("they hit, fight", 5);
+        let mark = set.track(8);
+        set.insert_str("all ");
+        assert_eq!(set.text().slice_all(), "they all hit, fight");
+        assert_eq!(set.get(mark).slice_after(), ", fight");
+
+        // Inserting at the mark leaves it before the new text.
+        set.move_caret_to(mark);
+        set.insert_str(" and kick");
+        assert_eq!(set.get(mark).slice_after(), " and kick, fight");
+        assert_eq!(set.text().slice_after(), ", fight");
+
+        // Inserting after the mark leaves it alone.
+        assert!(set.move_caret(|cur| cur.at_next()));
+        set.insert_char('!');
+        assert_eq!(set.get(mark).byte_pos(), 12);
+        assert_eq!(set.text().slice_all(), "they all hit and kick,! fight");
+
+        // Inserting inside the region before the mark shifts it.
+        set.move_caret(|cur| Some(StrCursor::new_at_start(cur.slice_all())));
+        set.insert_gc(::Gc::from_str("💪").unwrap());
+        assert_eq!(set.get(mark).byte_pos(), 16);
+        assert_eq!(set.text().byte_pos(), 4);
+    }
+
+    #[test]
+    fn test_position_set_delete() {
+        let mut set = position_set("they hit, fight, kick", 9);
+        let inside = set.track(12);
+        let after = set.track(17);
+        let before = set.track(2);
+        let end = set.track(16);
+        assert_eq!(set.delete_to(end), " fight,");
+        assert_eq!(set.text().slice_all(), "they hit, kick");
+        assert_eq!(set.get(inside).byte_pos(), 9);
+        assert_eq!(set.get(end).byte_pos(), 9);
+        assert_eq!(set.get(after).slice_after(), "kick");
+        assert_eq!(set.get(before).byte_pos(), 2);
+
+        assert_eq!(set.delete_prev().as_ref().map(|gc| gc.as_str()), Some(","));
+        assert_eq!(set.get(inside).byte_pos(), 8);
+        assert_eq!(set.get(after).slice_after(), "kick");
+        assert_eq!(set.delete_next().as_ref().map(|gc| gc.as_str()), Some(" "));
+        assert_eq!(set.get(after).byte_pos(), 8);
+        assert_eq!(set.into_text().into_string(), "they hitkick");
+    }
+
+    #[test]
+    fn test_position_set_combining() {
+        // Inserting a combining mark attaches it to the preceding base, so a mark tracked at that point is moved past the combined cluster.
+        let mut set = position_set("ex", 1);
+        let mark = set.track_caret();
+        let next = set.track(2);
+        set.insert_char('\u{301}');
+        assert_eq!(set.text().slice_before(), "e\u{301}");
+        assert_eq!(set.get(mark).byte_pos(), 3);
+        assert_eq!(set.get(next).byte_pos(), 4);
+
+        // Deleting a cluster which merges its neighbours moves marks left to the new boundary.
+        let mut set = position_set("\u{1100}a\u{1161}", 4);
+        let mark = set.track_caret();
+        assert_eq!(set.delete_prev().as_ref().map(|gc| gc.as_str()), Some("a"));
+        assert_eq!(set.get(mark).byte_pos(), 0);
+        assert_eq!(set.text().byte_pos(), 0);
+    }
+}
@@ -0,0 +1,214 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Diagnostic rendering of a cursor position.
+*/
+use alloc::string::String;
+use alloc::vec::Vec;
+use grapheme::Gc;
+use uniseg::UnicodeSegmentation as UniSeg;
+use StrCursor;
+
+/**
+Options controlling the output of [`StrCursor::render_caret`](struct.StrCursor.html#method.render_caret).
+
+The defaults are a `^` caret, a maximum width of 80 columns, tabs rendered as four spaces, and no line/column numbers.
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CaretOptions {
+    caret: char,
+    max_width: usize,
+    tab_width: usize,
+    line_col: bool,
+}
+
+impl CaretOptions {
+    /**
+    Create a new set of options with the default values.
+    */
+    pub fn new() -> CaretOptions {
+        CaretOptions {
+            caret: '^',
+            max_width: 80,
+            tab_width: 4,
+            line_col: false,
+        }
+    }
+
+    /**
+    Sets the character used to mark the cursor position.
+    */
+    pub fn caret(mut self, caret: char) -> CaretOptions {
+        self.caret = caret;
+        self
+    }
+
+    /**
+    Sets the maximum display width of the rendered line.
+
+    Lines wider than this are trimmed to a window of whole grapheme clusters centred, as far as possible, on the cursor.
+    */
+    pub fn max_width(mut self, max_width: usize) -> CaretOptions {
+        self.max_width = max_width;
+        self
+    }
+
+    /**
+    Sets the number of spaces each tab is rendered as.
+    */
+    pub fn tab_width(mut self, tab_width: usize) -> CaretOptions {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /**
+    Sets whether the 1-based line and column numbers of the cursor are appended after the caret.
+    */
+    pub fn line_col(mut self, line_col: bool) -> CaretOptions {
+        self.line_col = line_col;
+        self
+    }
+}
+
+impl Default for CaretOptions {
+    fn default() -> CaretOptions {
+        CaretOptions::new()
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Renders the line containing the cursor, followed by a second line with a caret positioned underneath the cursor.
+
+    Column positions are computed using the display width of each grapheme cluster, so the caret lines up correctly beneath wide (*e.g.* CJK) text.  Tabs are expanded to a fixed number of spaces, and lines wider than the configured maximum are trimmed without splitting any grapheme clusters.
+
+    The result does not end with a line terminator.
+
+    Requires the `width` feature.
+    */
+    pub fn render_caret(&self, opts: CaretOptions) -> String {
+        let start = ::lines::line_start(*self, ::LineTerminators::default());
+        let end = ::lines::line_end(*self, ::LineTerminators::default());
+        let line = start.slice_between(end).unwrap_or("");
+        let before = start.slice_between(*self).unwrap_or("");
+
+        let cells: Vec<(&str, usize)> = UniSeg::graphemes(line, /*is_extended:*/true)
+            .map(|gr| (gr, cell_width(gr, opts.tab_width)))
+            .collect();
+        let at = UniSeg::graphemes(before, /*is_extended:*/true).count();
+
+        // Always include the cluster under the cursor (or the column just past the end of the line), then grow the window left up to half the budget, right as far as it will go, and finally left again with whatever remains.
+        let (mut lo, mut hi) = (at, at);
+        let mut left_width = 0;
+        let mut used = 1;
+        if let Some(&(_, width)) = cells.get(at) {
+            hi += 1;
+            used = ::core::cmp::max(width, 1);
+        }
+        while lo > 0 && left_width + cells[lo - 1].1 <= opts.max_width / 2 {
+            lo -= 1;
+            left_width += cells[lo].1;
+            used += cells[lo].1;
+        }
+        while hi < cells.len() && used + cells[hi].1 <= opts.max_width {
+            used += cells[hi].1;
+            hi += 1;
+        }
+        while lo > 0 && used + cells[lo - 1].1 <= opts.max_width {
+            lo -= 1;
+            left_width += cells[lo].1;
+            used += cells[lo].1;
+        }
+
+        let mut out = String::with_capacity(line.len() + left_width + 2);
+        for &(gr, width) in &cells[lo..hi] {
+            if gr == "\t" {
+                for _ in 0..width {
+                    out.push(' ');
+                }
+            } else {
+                out.push_str(gr);
+            }
+        }
+        out.push('\n');
+        for _ in 0..left_width {
+            out.push(' ');
+        }
+        out.push(opts.caret);
+        if opts.line_col {
+            let (line, offset) = ::lines::line_and_offset(*self, ::LineTerminators::default());
+            out.push_str(&format!(" {}:{}", line, offset + 1));
+        }
+        out
+    }
+}
+
+fn cell_width(gr: &str, tab_width: usize) -> usize {
+    if gr == "\t" {
+        tab_width
+    } else {
+        unsafe { Gc::from_str_unchecked(gr) }.width()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaretOptions;
+    use StrCursor;
+
+    fn at(s: &str, byte_pos: usize) -> StrCursor {
+        StrCursor::new_at_left_of_byte_pos(s, byte_pos)
+    }
+
+    #[test]
+    fn test_render_caret_ascii() {
+        let s = "let x = 1;\nlet y = + 2;\n";
+        let cur = at(s, 19);
+        assert_eq!(cur.render_caret(CaretOptions::new()),
+            "let y = + 2;\n        ^");
+        assert_eq!(cur.render_caret(CaretOptions::new().caret('~').line_col(true)),
+            "let y = + 2;\n        ~ 2:9");
+
+        let cur = StrCursor::new_at_end(s);
+        assert_eq!(cur.render_caret(CaretOptions::new().line_col(true)),
+            "\n^ 3:1");
+    }
+
+    #[test]
+    fn test_render_caret_cjk() {
+        let s = "本当のテスト";
+        let cur = at(s, 9);
+        assert_eq!(cur.render_caret(CaretOptions::new()),
+            "本当のテスト\n      ^");
+        assert_eq!(cur.render_caret(CaretOptions::new().max_width(6)),
+            "のテス\n  ^");
+    }
+
+    #[test]
+    fn test_render_caret_tabs() {
+        let s = "\tif x {\r\n\t\treturn;";
+        let cur = at(s, 11);
+        assert_eq!(cur.render_caret(CaretOptions::new()),
+            "        return;\n        ^");
+        assert_eq!(cur.render_caret(CaretOptions::new().tab_width(2).line_col(true)),
+            "    return;\n    ^ 2:3");
+    }
+
+    #[test]
+    fn test_render_caret_trimmed() {
+        let s = "abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(at(s, 13).render_caret(CaretOptions::new().max_width(9)),
+            "jklmnopqr\n    ^");
+        assert_eq!(at(s, 1).render_caret(CaretOptions::new().max_width(9)),
+            "abcdefghi\n ^");
+        assert_eq!(StrCursor::new_at_end(s).render_caret(CaretOptions::new().max_width(9)),
+            "stuvwxyz\n        ^");
+    }
+}
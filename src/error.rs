@@ -0,0 +1,174 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Error types.
+*/
+use core::fmt::{self, Display};
+#[cfg(feature = "alloc")] use alloc::string::String;
+#[cfg(feature = "std")] use std::error::Error;
+
+/**
+The reason a byte position could not be used to construct a cursor, returned from [`StrCursor::new_at_byte_pos`](struct.StrCursor.html#method.new_at_byte_pos).
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PosError {
+    /// The position was past the end of the string.
+    OutOfBounds,
+    /// The position was inside a code point.
+    NotCharBoundary,
+    /// The position was between two code points of the same grapheme cluster.
+    NotGcBoundary,
+}
+
+impl Display for PosError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            PosError::OutOfBounds => "byte position out of bounds",
+            PosError::NotCharBoundary => "byte position is not on a code point boundary",
+            PosError::NotGcBoundary => "byte position is not on a grapheme cluster boundary",
+        };
+        fmt.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PosError {}
+
+/**
+The error returned when converting a grapheme cluster which is not exactly one code point into a `char`.
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct NotCharError;
+
+impl Display for NotCharError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("grapheme cluster is not a single code point")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for NotCharError {}
+
+/**
+The reason a string could not be converted into a single grapheme cluster.
+
+This is returned from the checked `GcBuf` conversions, such as [`GcBuf::from_chars`](struct.GcBuf.html#method.from_chars) and the `FromStr` and `TryFrom` implementations.
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum GcParseError {
+    /// The string was empty.
+    Empty,
+    /// The string contained more than one grapheme cluster.
+    MultipleClusters {
+        /// The length, in bytes, of the first grapheme cluster in the string.
+        first_cluster_len: usize,
+    },
+}
+
+impl Display for GcParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            GcParseError::Empty => "string is empty",
+            GcParseError::MultipleClusters { .. } => "string contains more than one grapheme cluster",
+        };
+        fmt.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for GcParseError {}
+
+/**
+The error returned when converting a `String` which is not a single grapheme cluster into a `GcBuf`.
+
+The original string can be recovered with [`into_string`](#method.into_string).
+
+Requires the `alloc` feature.
+*/
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FromStringError {
+    string: String,
+    error: GcParseError,
+}
+
+#[cfg(feature = "alloc")]
+impl FromStringError {
+    /**
+    Create a new error from the string which failed to convert, and the reason why.
+    */
+    pub(crate) fn new(string: String, error: GcParseError) -> FromStringError {
+        FromStringError { string, error }
+    }
+
+    /**
+    Returns the reason the conversion failed.
+    */
+    pub fn error(&self) -> GcParseError {
+        self.error
+    }
+
+    /**
+    Returns a reference to the string which failed to convert.
+    */
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    /**
+    Returns the string which failed to convert.
+    */
+    pub fn into_string(self) -> String {
+        self.string
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Display for FromStringError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.error, fmt)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for FromStringError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/**
+The reason a grapheme cluster could not be composed from a sequence of code points, returned from [`GcBuf::compose`](struct.GcBuf.html#method.compose) and [`GcBuf::try_from_chars`](struct.GcBuf.html#method.try_from_chars).
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ComposeError {
+    /// There were no code points.
+    Empty,
+    /// The code points did not form a single grapheme cluster.
+    Break {
+        /// The byte offset, within the concatenated code points, at which the first cluster ends and a second begins.
+        byte_pos: usize,
+        /// The index of the code point which begins the second cluster.
+        char_index: usize,
+    },
+}
+
+impl Display for ComposeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ComposeError::Empty => fmt.write_str("no code points to compose"),
+            ComposeError::Break { byte_pos, char_index } => write!(fmt,
+                "grapheme cluster breaks before code point {} (byte offset {})", char_index, byte_pos),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ComposeError {}
@@ -10,23 +10,109 @@ or distributed except according to those terms.
 /*!
 Defines types for representing single grapheme clusters.
 */
-use std::borrow::{Borrow, Cow, ToOwned};
-use std::convert::AsRef;
-use std::cmp::Ordering;
-use std::fmt::{self, Debug, Display};
-use std::mem::transmute;
-use std::ops::Deref;
+use core::borrow::Borrow;
+#[cfg(feature = "alloc")] use alloc::borrow::{Cow, ToOwned};
+use core::convert::{AsRef, TryFrom};
+#[cfg(feature = "alloc")] use core::str::FromStr;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+#[cfg(feature = "alloc")] use core::iter::FromIterator;
+use core::mem::transmute;
+use core::ops::Deref;
+#[cfg(feature = "alloc")] use alloc::boxed::Box;
+#[cfg(feature = "alloc")] use alloc::rc::Rc;
+#[cfg(feature = "alloc")] use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")] use alloc::sync::Arc;
+#[cfg(feature = "alloc")] use alloc::vec::Vec;
 use uniseg::UnicodeSegmentation as UniSeg;
+use NotCharError;
+#[cfg(feature = "alloc")] use {ComposeError, FromStringError, GcParseError};
 
 /**
 An iterator over the lower case mapping of a given grapheme cluster, returned from [`Gc::to_lowercase`](struct.Gc.html#method.to_lowercase).
 */
-pub type ToLowercase<'a> = ::std::iter::FlatMap<::std::str::Chars<'a>, ::std::char::ToLowercase, fn(char) -> ::std::char::ToLowercase>;
+pub type ToLowercase<'a> = ::core::iter::FlatMap<::core::str::Chars<'a>, ::core::char::ToLowercase, fn(char) -> ::core::char::ToLowercase>;
 
 /**
 An iterator over the lower case mapping of a given grapheme cluster, returned from [`Gc::to_uppercase`](struct.Gc.html#method.to_uppercase).
 */
-pub type ToUppercase<'a> = ::std::iter::FlatMap<::std::str::Chars<'a>, ::std::char::ToUppercase, fn(char) -> ::std::char::ToUppercase>;
+pub type ToUppercase<'a> = ::core::iter::FlatMap<::core::str::Chars<'a>, ::core::char::ToUppercase, fn(char) -> ::core::char::ToUppercase>;
+
+/**
+An iterator over the title case mapping of a given grapheme cluster, returned from [`Gc::to_titlecase`](struct.Gc.html#method.to_titlecase).
+*/
+#[derive(Clone, Debug)]
+pub struct ToTitlecase<'a> {
+    base: [char; 3],
+    base_at: usize,
+    base_len: usize,
+    marks: ::core::str::Chars<'a>,
+}
+
+impl<'a> Iterator for ToTitlecase<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.base_at < self.base_len {
+            self.base_at += 1;
+            Some(self.base[self.base_at - 1])
+        } else {
+            self.marks.next()
+        }
+    }
+}
+
+/**
+An iterator over the combining marks of a given grapheme cluster, returned from [`Gc::marks`](struct.Gc.html#method.marks).
+*/
+pub type Marks<'a> = ::core::str::Chars<'a>;
+
+/**
+An iterator over the parts of a grapheme cluster separated by zero width joiners, returned from [`Gc::zwj_parts`](struct.Gc.html#method.zwj_parts).
+*/
+pub type ZwjParts<'a> = ::core::str::Split<'a, char>;
+
+/**
+An iterator over the combining marks of a given grapheme cluster and their byte offsets, returned from [`Gc::mark_indices`](struct.Gc.html#method.mark_indices).
+*/
+#[derive(Clone, Debug)]
+pub struct MarkIndices<'a> {
+    offset: usize,
+    iter: ::core::str::CharIndices<'a>,
+}
+
+impl<'a> Iterator for MarkIndices<'a> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.iter.next().map(|(i, c)| (i + self.offset, c))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for MarkIndices<'a> {
+    fn next_back(&mut self) -> Option<(usize, char)> {
+        self.iter.next_back().map(|(i, c)| (i + self.offset, c))
+    }
+}
+
+/**
+A wrapper which displays a grapheme cluster with every code point escaped, returned from [`Gc::display_escaped`](struct.Gc.html#method.display_escaped).
+*/
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayEscaped<'a>(&'a Gc);
+
+impl<'a> Display for DisplayEscaped<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            Display::fmt(&c.escape_debug(), fmt)?;
+        }
+        Ok(())
+    }
+}
 
 /**
 A slice of a single Unicode grapheme cluster (GC) (akin to `str`).
@@ -46,6 +132,7 @@ One inconvenience when dealing with grapheme clusters in Rust is that they are n
 Hence, this type guarantees that it always represents *exactly* one Unicode grapheme cluster.
 */
 #[derive(Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
 pub struct Gc(str);
 
 impl Gc {
@@ -87,13 +174,58 @@ impl Gc {
         }
     }
 
+    /**
+    Try to split a single grapheme cluster from the end of `s`.
+
+    Returns the rest of the string along with its final grapheme cluster, or `None` if the given string was empty.  This is the mirror image of [`split_from`](#method.split_from).
+    */
+    pub fn rsplit_from(s: &str) -> Option<(&str, &Gc)> {
+        let gr = UniSeg::graphemes(s, /*is_extended:*/true).next_back()?;
+        let head = &s[..s.len() - gr.len()];
+        Some((head, unsafe { Gc::from_str_unchecked(gr) }))
+    }
+
+    /**
+    Returns the first grapheme cluster of `s`, or `None` if the given string was empty.
+    */
+    pub fn first_in(s: &str) -> Option<&Gc> {
+        Gc::split_from(s).map(|(gc, _)| gc)
+    }
+
+    /**
+    Returns the last grapheme cluster of `s`, or `None` if the given string was empty.
+    */
+    pub fn last_in(s: &str) -> Option<&Gc> {
+        Gc::rsplit_from(s).map(|(_, gc)| gc)
+    }
+
     /**
     Returns the length of this grapheme cluster in bytes.
+
+    "Length" is ambiguous for a grapheme cluster: it could reasonably mean bytes, code points, or (trivially) clusters.  Where it matters, prefer the explicit [`byte_len`](#method.byte_len) or [`char_count`](#method.char_count).
     */
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    /**
+    Returns the length of this grapheme cluster in bytes.
+
+    This is identical to [`len`](#method.len), but is explicit about the unit being measured.  See also [`char_count`](#method.char_count).
+    */
+    pub fn byte_len(&self) -> usize {
+        self.0.len()
+    }
+
+    /**
+    Returns the number of code points in this grapheme cluster.
+
+    This is always at least one.  Unlike [`len`](#method.len), which counts bytes, this counts `char`s.  See also [`byte_len`](#method.byte_len).
+    */
+    pub fn char_count(&self) -> usize {
+        self.0.chars().count()
+    }
+
     /**
     Does this grapheme cluster have additional marks applied to it?
 
@@ -131,6 +263,251 @@ impl Gc {
         }
     }
 
+    /**
+    Returns the code point this cluster consists of, if it is exactly one code point.
+
+    Unlike [`base_char`](#method.base_char), this returns `None` rather than discarding any marks.
+    */
+    pub fn try_as_char(&self) -> Option<char> {
+        let mut chars = self.0.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None
+        }
+    }
+
+    /**
+    Returns the byte this cluster consists of, if it is exactly one ASCII code point.
+
+    This is a cheaper alternative to [`try_as_char`](#method.try_as_char) for taking a byte-oriented fast path.  Since `"\r\n"` is a single cluster, but two code points, it yields `None`.
+    */
+    #[inline]
+    pub fn try_as_ascii_byte(&self) -> Option<u8> {
+        match *self.0.as_bytes() {
+            [b] if b.is_ascii() => Some(b),
+            _ => None
+        }
+    }
+
+    /**
+    Returns the single code point this cluster canonically composes to, if any.
+
+    This is the same as [`try_as_char`](#method.try_as_char), except that the cluster is first converted to Normalization Form C.  As such, `"e\u{301}"` yields `'\u{E9}'`, but `"x\u{301}"` yields `None`, as there is no precomposed form.
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn compose_char(&self) -> Option<char> {
+        use unicode_normalization::UnicodeNormalization;
+        let mut chars = self.0.nfc();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None
+        }
+    }
+
+    /**
+    Returns the length, in bytes, of the "base" code point.
+    */
+    pub fn base_len(&self) -> usize {
+        self.base_char().len_utf8()
+    }
+
+    /**
+    Returns the byte offset at which the combining marks begin.
+
+    Since the marks immediately follow the base code point, this is always equal to [`base_len`](#method.base_len).  If the cluster has no marks, this is equal to the length of the cluster.
+    */
+    pub fn marks_offset(&self) -> usize {
+        self.base_len()
+    }
+
+    /**
+    Is the "base" code point of this cluster equal to `c`?
+
+    This ignores any marks applied to the cluster.  Contrast this with comparing a `Gc` to a `char` using `==`, which is only `true` if the cluster has *no* marks: `gc("a\u{308}").base_is('a')` is `true`, whilst `gc("a\u{308}") == 'a'` is `false`.
+    */
+    pub fn base_is(&self, c: char) -> bool {
+        self.base_char() == c
+    }
+
+    /**
+    Is this cluster whitespace?
+
+    A cluster is only whitespace if *every* code point in it is whitespace; a space with a combining mark applied to it is *not* whitespace, since it is rendered as a visible glyph.
+    */
+    pub fn is_whitespace(&self) -> bool {
+        self.all_chars(char::is_whitespace)
+    }
+
+    /**
+    Is this cluster alphabetic?
+
+    This is determined by the base code point alone; combining marks do not affect the result.
+    */
+    pub fn is_alphabetic(&self) -> bool {
+        self.base_char().is_alphabetic()
+    }
+
+    /**
+    Is this cluster numeric?
+
+    This is determined by the base code point alone; combining marks (such as the enclosing keycap in `"7\u{20E3}"`) do not affect the result.
+    */
+    pub fn is_numeric(&self) -> bool {
+        self.base_char().is_numeric()
+    }
+
+    /**
+    Is this cluster alphanumeric?
+
+    This is determined by the base code point alone; combining marks do not affect the result.
+    */
+    pub fn is_alphanumeric(&self) -> bool {
+        self.base_char().is_alphanumeric()
+    }
+
+    /**
+    Is this cluster a control character?
+
+    This is determined by the base code point alone, so `"\r\n"` is a control cluster.
+    */
+    pub fn is_control(&self) -> bool {
+        self.base_char().is_control()
+    }
+
+    /**
+    Is this cluster a single ASCII code point?
+
+    Clusters with marks are never ASCII, even if their base is.  Note that this means `"\r\n"` is *not* ASCII, as it is two code points.
+    */
+    pub fn is_ascii(&self) -> bool {
+        self.0.len() == 1 && self.0.as_bytes()[0] < 0x80
+    }
+
+    /**
+    Returns the ASCII byte this cluster consists of, or `None` if it is not a single ASCII code point.
+    */
+    pub fn to_ascii_char(&self) -> Option<u8> {
+        if self.is_ascii() {
+            Some(self.0.as_bytes()[0])
+        } else {
+            None
+        }
+    }
+
+    /**
+    Are these clusters equal, ignoring ASCII case?
+
+    Clusters which are not ASCII (see [`is_ascii`](#method.is_ascii)) must match exactly.
+    */
+    pub fn eq_ignore_ascii_case(&self, other: &Gc) -> bool {
+        match (self.to_ascii_char(), other.to_ascii_char()) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(&b),
+            _ => self.0 == other.0
+        }
+    }
+
+    /**
+    Returns a copy of this cluster converted to ASCII lower case.  Clusters which are not ASCII are returned unchanged.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_ascii_lowercase(&self) -> GcBuf {
+        match self.to_ascii_char() {
+            Some(b) => GcBuf::from(b.to_ascii_lowercase() as char),
+            None => GcBuf::from(self)
+        }
+    }
+
+    /**
+    Returns a copy of this cluster converted to ASCII upper case.  Clusters which are not ASCII are returned unchanged.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_ascii_uppercase(&self) -> GcBuf {
+        match self.to_ascii_char() {
+            Some(b) => GcBuf::from(b.to_ascii_uppercase() as char),
+            None => GcBuf::from(self)
+        }
+    }
+
+    /**
+    Converts this cluster to a digit in the given radix, as per `char::to_digit`.
+
+    Returns `None` if the cluster is not a digit in that radix, or if it has any marks.
+
+    # Panics
+
+    If `radix` is greater than 36, then this function will panic.
+    */
+    pub fn to_digit(&self, radix: u32) -> Option<u32> {
+        if self.has_marks() {
+            return None;
+        }
+        self.base_char().to_digit(radix)
+    }
+
+    /**
+    Returns the value of this cluster as a decimal digit in any script.
+
+    Unlike `to_digit`, this supports every run of decimal digits (General_Category `Nd`) defined by Unicode, not just ASCII: this includes Arabic-Indic (`٠`–`٩`), Extended Arabic-Indic (`۰`–`۹`), Devanagari (`०`–`९`) and the other Indic scripts, Thai, Tibetan, Myanmar, fullwidth (`０`–`９`), mathematical alphanumeric digits, and so on.  It does *not* include other numeric characters, such as superscripts, circled digits, or Roman numerals.
+
+    Returns `None` if the cluster is not a decimal digit, or if it has any marks.
+    */
+    pub fn decimal_digit_value(&self) -> Option<u32> {
+        if self.has_marks() {
+            return None;
+        }
+        decimal_digit_value(self.base_char())
+    }
+
+    /**
+    Does every code point in this cluster satisfy `pred`?
+    */
+    pub fn all_chars<P>(&self, pred: P) -> bool
+    where P: FnMut(char) -> bool {
+        self.0.chars().all(pred)
+    }
+
+    /**
+    Does any code point in this cluster satisfy `pred`?
+    */
+    pub fn any_char<P>(&self, pred: P) -> bool
+    where P: FnMut(char) -> bool {
+        self.0.chars().any(pred)
+    }
+
+    /**
+    Does this cluster contain a ZERO WIDTH JOINER (`U+200D`)?
+
+    Zero width joiners are used to combine several emoji into a single glyph, such as the members of a family.
+    */
+    pub fn has_zwj(&self) -> bool {
+        self.0.contains('\u{200D}')
+    }
+
+    /**
+    Returns an iterator over the parts of this cluster separated by ZERO WIDTH JOINER (`U+200D`), not including the joiners themselves.
+
+    For example, the family emoji `"👨\u{200D}👩\u{200D}👧"` yields `"👨"`, `"👩"` and `"👧"`.  Each part retains any modifiers or variation selectors applied to it.  A cluster with no zero width joiners yields a single part: the whole cluster.
+    */
+    pub fn zwj_parts(&self) -> ZwjParts<'_> {
+        self.0.split('\u{200D}')
+    }
+
+    /**
+    Does this cluster contain a variation selector?
+
+    This includes the emoji and text presentation selectors (`U+FE0F` and `U+FE0E`), along with all other code points with the Variation_Selector property.
+    */
+    pub fn has_variation_selector(&self) -> bool {
+        self.any_char(|c| matches!(c,
+            '\u{180B}'..='\u{180D}' | '\u{180F}' | '\u{FE00}'..='\u{FE0F}' | '\u{E0100}'..='\u{E01EF}'))
+    }
+
     /**
     Returns the "base" code point as a grapheme cluster.
 
@@ -138,12 +515,19 @@ impl Gc {
     */
     pub fn base(&self) -> &Gc {
         unsafe {
-            let base_cp = self.base_char();
-            let base_len = base_cp.len_utf8();
-            Gc::from_str_unchecked(self.0.slice_unchecked(base_len, self.0.len()))
+            Gc::from_str_unchecked(self.0.get_unchecked(..self.base_len()))
         }
     }
 
+    /**
+    Returns this cluster with any combining marks removed.
+
+    This is an alias for [`base`](#method.base).  A single code point is always a complete grapheme cluster, so the result is borrowed from this cluster.
+    */
+    pub fn strip_marks(&self) -> &Gc {
+        self.base()
+    }
+
     /**
     Returns the combining marks as a string slice.
 
@@ -151,45 +535,618 @@ impl Gc {
     */
     pub fn mark_str(&self) -> &str {
         unsafe {
-            let base_cp = self.base_char();
-            let base_len = base_cp.len_utf8();
-            self.0.slice_unchecked(base_len, self.0.len())
+            self.0.get_unchecked(self.marks_offset()..)
+        }
+    }
+
+    /**
+    An iterator over the combining marks of this grapheme cluster; that is, every code point after the base.
+    */
+    pub fn marks(&self) -> Marks<'_> {
+        self.mark_str().chars()
+    }
+
+    /**
+    Splits this grapheme cluster into its base code point and an iterator over its combining marks.
+
+    This is equivalent to `(self.base_char(), self.marks())`, but only decodes the base code point once.
+    */
+    pub fn decompose(&self) -> (char, Marks<'_>) {
+        let mut chars = self.0.chars();
+        match chars.next() {
+            Some(base) => (base, chars),
+            None => unsafe { debug_unreachable!() },
+        }
+    }
+
+    /**
+    An iterator over the combining marks of this grapheme cluster, and their byte offsets.
+
+    Offsets are relative to the start of the *cluster*, not the start of [`mark_str`](#method.mark_str).
+    */
+    pub fn mark_indices(&self) -> MarkIndices<'_> {
+        MarkIndices {
+            offset: self.marks_offset(),
+            iter: self.mark_str().char_indices(),
         }
     }
 
     /**
     An iterator over the code points of this grapheme cluster.
     */
-    pub fn chars(&self) -> ::std::str::Chars {
+    pub fn chars(&self) -> ::core::str::Chars {
         self.0.chars()
     }
 
-    /**
-    An iterator over the code points of this grapheme cluster, and their associated byte offsets.
-    */
-    pub fn char_indices(&self) -> ::std::str::CharIndices {
-        self.0.char_indices()
+    /**
+    An iterator over the code points of this grapheme cluster, and their associated byte offsets.
+    */
+    pub fn char_indices(&self) -> ::core::str::CharIndices {
+        self.0.char_indices()
+    }
+
+    /**
+    An iterator over the bytes of this grapheme cluster.
+    */
+    pub fn bytes(&self) -> ::core::str::Bytes {
+        self.0.bytes()
+    }
+
+    /**
+    Returns this grapheme cluster with each code point escaped as by `char::escape_debug`.
+
+    Unlike `str::escape_debug`, combining marks are *always* escaped, so invisible code points such as zero width joiners and variation selectors are visible in the output: `"e\u{301}"` is escaped as `"e\\u{301}"`.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn escape_debug(&self) -> String {
+        self.display_escaped().to_string()
+    }
+
+    /**
+    Returns this grapheme cluster with every code point escaped as by `char::escape_unicode`.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn escape_unicode(&self) -> String {
+        self.0.chars().flat_map(char::escape_unicode).collect()
+    }
+
+    /**
+    Returns an adapter which displays this grapheme cluster escaped as by [`escape_debug`](#method.escape_debug), without allocating.
+    */
+    pub fn display_escaped(&self) -> DisplayEscaped<'_> {
+        DisplayEscaped(self)
+    }
+
+    /**
+    Returns the display width of this grapheme cluster, in columns.
+
+    A cluster is displayed as a single glyph, so its width is *not* simply the sum of the widths of its code points.  Instead, this uses the larger of the width of the base code point, and the width of the cluster as a whole as computed by the `unicode-width` crate (which treats emoji presentation, flag and ZWJ sequences as a single wide glyph, and combining marks as zero width).  Clusters whose base is a control character (such as tabs and line terminators) have a width of zero.
+
+    Requires the `width` feature.
+    */
+    #[cfg(feature = "width")]
+    pub fn width(&self) -> usize {
+        use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+        match UnicodeWidthChar::width(self.base_char()) {
+            Some(base) => ::core::cmp::max(base, UnicodeWidthStr::width(&self.0)),
+            None => 0
+        }
+    }
+
+    /**
+    Returns a string consisting of this grapheme cluster repeated `n` times.
+
+    Note that the result is *not* guaranteed to be `n` grapheme clusters long, since some clusters (such as regional indicators and Hangul jamo) can merge with copies of themselves.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn repeat(&self, n: usize) -> String {
+        self.0.repeat(n)
+    }
+
+    /**
+    Returns a string consisting of this grapheme cluster repeated as many times as will fit in `cols` columns of display width.
+
+    The result never exceeds `cols` columns; if the width of this cluster does not evenly divide `cols`, the result will be narrower.  If this cluster has a width of zero, the result is empty.
+
+    Requires the `width` feature.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "width")]
+    #[cfg(feature = "alloc")]
+    pub fn repeat_to_width(&self, cols: usize) -> String {
+        match self.width() {
+            0 => String::new(),
+            width => self.repeat(cols / width)
+        }
+    }
+
+    /**
+    Returns the Unicode General_Category of this grapheme cluster.
+
+    Only the category of the *base* code point is returned; any marks applied to the cluster are ignored.  As such, `"e\u{301}"` is `Ll`, not `Mn`.
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn general_category(&self) -> ::GeneralCategory {
+        ::GeneralCategory::of(self.base_char())
+    }
+
+    /**
+    Returns the Unicode General_Category of the base code point of this grapheme cluster.
+
+    This is the same as [`general_category`](#method.general_category).
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn base_category(&self) -> ::GeneralCategory {
+        self.general_category()
+    }
+
+    /**
+    Returns the Unicode Script of the base code point of this grapheme cluster.
+
+    Only the base code point is classified.  Combining marks generally have the `Inherited` script, taking on the script of whatever they are applied to, so this is usually the script of the cluster as a whole.  Code points shared between scripts, such as punctuation and digits, have the `Common` script.
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn script(&self) -> ::Script {
+        ::unicode_script::UnicodeScript::script(&self.base_char())
+    }
+
+    /**
+    Is the base code point of this cluster a letter (General_Category `L`)?
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn is_letter(&self) -> bool {
+        self.base_category().is_letter()
+    }
+
+    /**
+    Does this cluster consist solely of combining marks (General_Category `M`)?
+
+    This is the case for a mark which appears at the start of a string, or after a control character, and so has no base to attach to.
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn is_mark_only(&self) -> bool {
+        self.all_chars(::props::is_mark_char)
+    }
+
+    /**
+    Is the base code point of this cluster punctuation (General_Category `P`)?
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn is_punctuation(&self) -> bool {
+        self.base_category().is_punctuation()
+    }
+
+    /**
+    Is the base code point of this cluster a symbol (General_Category `S`)?
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn is_symbol(&self) -> bool {
+        self.base_category().is_symbol()
+    }
+
+    /**
+    Is this grapheme cluster displayed as an emoji by default?
+
+    This is `true` for emoji which default to emoji presentation (such as `"👍"`), emoji modifier sequences (`"👍🏽"`), flags (`"🇦🇺"`, but not a lone regional indicator), keycaps (`"#\u{FE0F}\u{20E3}"`), ZWJ sequences, and emoji followed by the emoji presentation selector (`"♥\u{FE0F}"`).  It is `false` for emoji which default to text presentation and are not otherwise modified (`"♥"`), and for any emoji followed by the text presentation selector.
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn is_emoji(&self) -> bool {
+        use unicode_properties::{EmojiStatus, UnicodeEmoji};
+        let base = self.base_char();
+        if !base.is_emoji_char() {
+            return false;
+        }
+        if self.mark_str().contains('\u{20E3}') {
+            return matches!(base, '#' | '*' | '0'..='9');
+        }
+        if let '\u{1F1E6}'..='\u{1F1FF}' = base {
+            // Only a pair of regional indicators forms a flag.
+            return matches!(self.marks().next(), Some('\u{1F1E6}'..='\u{1F1FF}'));
+        }
+        match self.marks().next() {
+            Some('\u{FE0E}') => false,
+            Some('\u{FE0F}') | Some('\u{200D}') | Some('\u{1F3FB}'..='\u{1F3FF}') => true,
+            _ => matches!(base.emoji_status(),
+                EmojiStatus::EmojiPresentation
+                | EmojiStatus::EmojiPresentationAndModifierBase
+                | EmojiStatus::EmojiPresentationAndEmojiComponent
+                | EmojiStatus::EmojiPresentationAndModifierAndEmojiComponent)
+        }
+    }
+
+    /**
+    Does the base code point of this grapheme cluster have the Extended_Pictographic property?
+
+    This is a property of the base code point alone, and is used to determine the default presentation of a pictograph; it does not consider whether the cluster as a whole forms an emoji sequence.  See the "Compatibility" section of the crate documentation for the version of Unicode used.
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn is_extended_pictographic(&self) -> bool {
+        ::props::is_extended_pictographic(self.base_char())
+    }
+
+    /**
+    Returns this grapheme cluster in Normalization Form C (canonical composition).
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn nfc(&self) -> GcBuf {
+        use unicode_normalization::UnicodeNormalization;
+        let s: String = self.0.nfc().collect();
+        // Canonical composition cannot join or split grapheme clusters, so the result is still a single cluster.
+        debug_assert!(Gc::from_str(&s).is_some());
+        unsafe { GcBuf::from_string_unchecked(s) }
+    }
+
+    /**
+    Returns this grapheme cluster in Normalization Form D (canonical decomposition).
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn nfd(&self) -> GcBuf {
+        use unicode_normalization::UnicodeNormalization;
+        let s: String = self.0.nfd().collect();
+        // Canonical decomposition cannot join or split grapheme clusters, so the result is still a single cluster.
+        debug_assert!(Gc::from_str(&s).is_some());
+        unsafe { GcBuf::from_string_unchecked(s) }
+    }
+
+    /**
+    Are these clusters canonically equivalent?
+
+    This compares the Normalization Form D of both clusters, so `"\u{E9}"` is equivalent to `"e\u{301}"`, and a precomposed Hangul syllable is equivalent to its conjoining jamo.  The decompositions are compared incrementally, without allocating.
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn canonical_eq(&self, other: &Gc) -> bool {
+        use unicode_normalization::UnicodeNormalization;
+        self.0 == other.0 || self.0.nfd().eq(other.0.nfd())
+    }
+
+    /**
+    Are the code points in this cluster in canonical order?
+
+    This checks that every run of non-starters (code points with a non-zero Canonical_Combining_Class, such as most combining marks) is in non-decreasing order of combining class.  A cluster which fails this check is still valid text, but is not in any normalization form; for example, `"a\u{302}\u{323}"` is out of order, since U+0323 (class 220) should come before U+0302 (class 230).  Normalizing the cluster with [`nfc`](#method.nfc) or [`nfd`](#method.nfd) puts it in canonical order.
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn all_marks_are_ordered(&self) -> bool {
+        use unicode_normalization::char::canonical_combining_class;
+        let mut prev = 0;
+        for c in self.0.chars() {
+            let ccc = canonical_combining_class(c);
+            if ccc != 0 && ccc < prev {
+                return false;
+            }
+            prev = ccc;
+        }
+        true
+    }
+
+    /**
+    Compares these clusters, treating canonically equivalent clusters as equal.
+
+    This orders clusters by the code points of their Normalization Form C, so `"\u{E9}"` and `"e\u{301}"` compare as `Equal`, and this ordering is consistent with [`canonical_eq`](#method.canonical_eq).  It is *not* consistent with the `Ord` implementation for `Gc`, which compares the clusters' bytes as-is.
+
+    Both clusters are normalized on every call.  The normalized forms are produced incrementally without allocating, but this is still considerably more expensive than an ordinary comparison; when sorting many clusters, consider normalizing them once up front with [`nfc`](#method.nfc) instead.
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn cmp_canonical(&self, other: &Gc) -> Ordering {
+        use unicode_normalization::UnicodeNormalization;
+        if self.0 == other.0 {
+            return Ordering::Equal;
+        }
+        self.0.nfc().cmp(other.0.nfc())
+    }
+
+    /**
+    Returns an iterator over the code points in the lower case equivalent of this grapheme cluster.
+    */
+    pub fn to_lowercase(&self) -> ToLowercase {
+        self.0.chars().flat_map(char::to_lowercase)
+    }
+
+    /**
+    Returns an iterator over the code points in the upper case equivalent of this grapheme cluster.
+    */
+    pub fn to_uppercase(&self) -> ToUppercase {
+        self.0.chars().flat_map(char::to_uppercase)
+    }
+
+    /**
+    Are these clusters equal, ignoring case?
+
+    Both clusters are case folded by mapping them to lower case, upper case, then lower case again, and the resulting code point sequences are compared without allocating.  This means that mappings which change the number of code points are handled correctly: `"ß"` is equal to `"ẞ"`, and `"İ"` is equal to `"i\u{307}"`.  Marks are compared as-is after folding, so `"A\u{308}"` is equal to `"a\u{308}"`, but not to `"\u{E4}"`.
+    */
+    pub fn eq_ignore_case(&self, other: &Gc) -> bool {
+        self.0 == other.0 || fold_case(&self.0).eq(fold_case(&other.0))
+    }
+
+    /**
+    Is this cluster equal to `s`, ignoring case?
+
+    The code points of both are case folded and compared lazily, exactly as for [`eq_ignore_case`](#method.eq_ignore_case), so no allocation is performed.  Since the comparison is of code points, `s` need not be a single grapheme cluster: `"ß"` is equal to `"ss"`, as both fold to the same code points.  If both are ASCII, a faster ASCII-only comparison is used.
+    */
+    pub fn eq_str_ignore_case(&self, s: &str) -> bool {
+        if self.0.is_ascii() && s.is_ascii() {
+            return self.0.eq_ignore_ascii_case(s);
+        }
+        fold_case(&self.0).eq(fold_case(s))
+    }
+
+    /**
+    Returns an iterator over the code points in the title case equivalent of this grapheme cluster.
+
+    Only the base code point is title cased; any marks are passed through unchanged.  Title case differs from upper case for digraphs such as `'ǳ'` (which becomes `'ǲ'`, not `'Ǳ'`), ligatures such as `'ﬁ'` (which becomes `"Fi"`), and Greek letters with a subscript iota.
+    */
+    pub fn to_titlecase(&self) -> ToTitlecase<'_> {
+        let (base, base_len) = titlecase(self.base_char());
+        ToTitlecase {
+            base,
+            base_at: 0,
+            base_len,
+            marks: self.mark_str().chars(),
+        }
+    }
+
+    /**
+    Returns the lower case equivalent of this grapheme cluster as a string.
+
+    The result is borrowed from this cluster if it is already lower case.  Note that lower casing a single cluster cannot take its surrounding context into account, so `"Σ"` always becomes `"σ"`, never the final form `"ς"`.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_lowercase_str(&self) -> Cow<'_, str> {
+        map_str(&self.0, self.to_lowercase())
+    }
+
+    /**
+    Returns the upper case equivalent of this grapheme cluster as a string.
+
+    The result is borrowed from this cluster if it is already upper case.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_uppercase_str(&self) -> Cow<'_, str> {
+        map_str(&self.0, self.to_uppercase())
+    }
+
+    /**
+    Returns the title case equivalent of this grapheme cluster as a string.
+
+    See [`to_titlecase`](#method.to_titlecase).  The result is borrowed from this cluster if it is already title case.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_titlecase_str(&self) -> Cow<'_, str> {
+        map_str(&self.0, self.to_titlecase())
+    }
+
+    /**
+    Returns the lower case equivalent of this grapheme cluster, if it is a single grapheme cluster.
+
+    Case mappings can change the number of code points, and occasionally produce more than one cluster, in which case this returns `None`.  For example, `"İ"` lower cases to the single cluster `"i\u{307}"`.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_lowercase_gc(&self) -> Option<GcBuf> {
+        single_gc(self.to_lowercase_str())
+    }
+
+    /**
+    Returns the upper case equivalent of this grapheme cluster, if it is a single grapheme cluster.
+
+    For example, `"ß"` upper cases to `"SS"`, which is *two* clusters, so this returns `None`.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_uppercase_gc(&self) -> Option<GcBuf> {
+        single_gc(self.to_uppercase_str())
+    }
+
+    /**
+    Returns the title case equivalent of this grapheme cluster, if it is a single grapheme cluster.
+
+    For example, `"ß"` title cases to `"Ss"`, which is *two* clusters, so this returns `None`.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn to_titlecase_gc(&self) -> Option<GcBuf> {
+        single_gc(self.to_titlecase_str())
+    }
+}
+
+/**
+Returns the result of a code point mapping over `s`, borrowing `s` if the mapping leaves it unchanged.
+*/
+#[cfg(feature = "alloc")]
+fn map_str<I>(s: &str, mapped: I) -> Cow<'_, str>
+where I: Iterator<Item=char> + Clone {
+    if mapped.clone().eq(s.chars()) {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(mapped.collect())
+    }
+}
+
+/**
+Converts `s` into a `GcBuf`, reporting where the first cluster ends if it is not exactly one grapheme cluster.
+*/
+#[cfg(feature = "alloc")]
+fn compose_string(s: String) -> Result<GcBuf, ComposeError> {
+    match check_single_gc(&s) {
+        Ok(()) => Ok(unsafe { GcBuf::from_string_unchecked(s) }),
+        Err(GcParseError::Empty) => Err(ComposeError::Empty),
+        Err(GcParseError::MultipleClusters { first_cluster_len }) => Err(ComposeError::Break {
+            byte_pos: first_cluster_len,
+            char_index: s[..first_cluster_len].chars().count(),
+        }),
+    }
+}
+
+/**
+Case folds `s` by mapping it to lower case, upper case, then lower case again.
+*/
+fn fold_case(s: &str) -> impl Iterator<Item=char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+        .flat_map(char::to_uppercase).flat_map(char::to_lowercase)
+}
+
+/**
+Checks that `s` is exactly one grapheme cluster.
+*/
+#[cfg(feature = "alloc")]
+fn check_single_gc(s: &str) -> Result<(), GcParseError> {
+    match Gc::split_from(s) {
+        None => Err(GcParseError::Empty),
+        Some((gc, tail)) if !tail.is_empty() => Err(GcParseError::MultipleClusters {
+            first_cluster_len: gc.as_str().len(),
+        }),
+        Some(_) => Ok(())
+    }
+}
+
+/**
+Converts `s` into a `GcBuf`, if it is exactly one grapheme cluster.
+*/
+#[cfg(feature = "alloc")]
+fn single_gc(s: Cow<str>) -> Option<GcBuf> {
+    if Gc::from_str(&s).is_some() {
+        Some(unsafe { GcBuf::from_string_unchecked(s.into_owned()) })
+    } else {
+        None
     }
+}
 
-    /**
-    An iterator over the bytes of this grapheme cluster.
-    */
-    pub fn bytes(&self) -> ::std::str::Bytes {
-        self.0.bytes()
-    }
+/**
+The zero digit of every run of decimal digits (General_Category `Nd`).  Each run consists of ten consecutive code points with the values zero through nine.
 
-    /**
-    Returns an iterator over the code points in the lower case equivalent of this grapheme cluster.
-    */
-    pub fn to_lowercase(&self) -> ToLowercase {
-        self.0.chars().flat_map(char::to_lowercase)
-    }
+This is from the Unicode version given in the crate's "Compatibility" documentation, and `test_decimal_digit_table` checks it against `unicode-properties`.
+*/
+const DECIMAL_DIGIT_ZEROS: &[u32] = &[
+    0x30, 0x660, 0x6F0, 0x7C0, 0x966, 0x9E6, 0xA66, 0xAE6,
+    0xB66, 0xBE6, 0xC66, 0xCE6, 0xD66, 0xDE6, 0xE50, 0xED0,
+    0xF20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900,
+    0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10, 0x104A0, 0x10D30, 0x10D40,
+    0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0, 0x11650,
+    0x116C0, 0x116D0, 0x116DA, 0x11730, 0x118E0, 0x11950, 0x11BF0, 0x11C50,
+    0x11D50, 0x11DA0, 0x11DE0, 0x11F50, 0x16130, 0x16A60, 0x16AC0, 0x16B50,
+    0x16D70, 0x1CCF0, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140,
+    0x1E2F0, 0x1E4F0, 0x1E5F1, 0x1E950, 0x1FBF0,
+];
 
-    /**
-    Returns an iterator over the code points in the upper case equivalent of this grapheme cluster.
-    */
-    pub fn to_uppercase(&self) -> ToUppercase {
-        self.0.chars().flat_map(char::to_uppercase)
+/**
+Returns the value of a decimal digit (General_Category `Nd`).
+*/
+fn decimal_digit_value(c: char) -> Option<u32> {
+    let c = c as u32;
+    let zero = match DECIMAL_DIGIT_ZEROS.binary_search(&c) {
+        Ok(i) => DECIMAL_DIGIT_ZEROS[i],
+        Err(0) => return None,
+        Err(i) => DECIMAL_DIGIT_ZEROS[i - 1],
+    };
+    if c - zero < 10 { Some(c - zero) } else { None }
+}
+
+/**
+Returns the title case mapping of a code point.
+
+The standard library does not provide title case mappings, so the code points whose title case mapping differs from their upper case mapping (per `UnicodeData.txt` and `SpecialCasing.txt`) are handled here explicitly.
+*/
+fn titlecase(c: char) -> ([char; 3], usize) {
+    let mapped: &[char] = match c {
+        '\u{1C4}'..='\u{1C6}' => &['\u{1C5}'],
+        '\u{1C7}'..='\u{1C9}' => &['\u{1C8}'],
+        '\u{1CA}'..='\u{1CC}' => &['\u{1CB}'],
+        '\u{1F1}'..='\u{1F3}' => &['\u{1F2}'],
+        '\u{DF}' => &['S', 's'],
+        '\u{FB00}' => &['F', 'f'],
+        '\u{FB01}' => &['F', 'i'],
+        '\u{FB02}' => &['F', 'l'],
+        '\u{FB03}' => &['F', 'f', 'i'],
+        '\u{FB04}' => &['F', 'f', 'l'],
+        '\u{FB05}' | '\u{FB06}' => &['S', 't'],
+        '\u{587}' => &['\u{535}', '\u{582}'],
+        '\u{FB13}' => &['\u{544}', '\u{576}'],
+        '\u{FB14}' => &['\u{544}', '\u{565}'],
+        '\u{FB15}' => &['\u{544}', '\u{56B}'],
+        '\u{FB16}' => &['\u{54E}', '\u{576}'],
+        '\u{FB17}' => &['\u{544}', '\u{56D}'],
+        '\u{1F80}'..='\u{1F87}' | '\u{1F90}'..='\u{1F97}' | '\u{1FA0}'..='\u{1FA7}' => {
+            let t = unsafe { ::core::char::from_u32_unchecked(c as u32 + 8) };
+            return ([t, '\0', '\0'], 1);
+        },
+        '\u{1F88}'..='\u{1F8F}' | '\u{1F98}'..='\u{1F9F}' | '\u{1FA8}'..='\u{1FAF}'
+        | '\u{1FBC}' | '\u{1FCC}' | '\u{1FFC}' => return ([c, '\0', '\0'], 1),
+        // Georgian Mkhedruli letters are their own title case, but upper case to Mtavruli.
+        '\u{10D0}'..='\u{10FA}' | '\u{10FD}'..='\u{10FF}' => return ([c, '\0', '\0'], 1),
+        '\u{1FB3}' => &['\u{1FBC}'],
+        '\u{1FC3}' => &['\u{1FCC}'],
+        '\u{1FF3}' => &['\u{1FFC}'],
+        '\u{1FB2}' => &['\u{1FBA}', '\u{345}'],
+        '\u{1FB4}' => &['\u{386}', '\u{345}'],
+        '\u{1FC2}' => &['\u{1FCA}', '\u{345}'],
+        '\u{1FC4}' => &['\u{389}', '\u{345}'],
+        '\u{1FF2}' => &['\u{1FFA}', '\u{345}'],
+        '\u{1FF4}' => &['\u{38F}', '\u{345}'],
+        '\u{1FB7}' => &['\u{391}', '\u{342}', '\u{345}'],
+        '\u{1FC7}' => &['\u{397}', '\u{342}', '\u{345}'],
+        '\u{1FF7}' => &['\u{3A9}', '\u{342}', '\u{345}'],
+        _ => {
+            let mut out = ['\0'; 3];
+            let mut len = 0;
+            for (slot, u) in out.iter_mut().zip(c.to_uppercase()) {
+                *slot = u;
+                len += 1;
+            }
+            return (out, len);
+        }
+    };
+    let mut out = ['\0'; 3];
+    out[..mapped.len()].copy_from_slice(mapped);
+    (out, mapped.len())
+}
+
+impl AsRef<Gc> for Gc {
+    fn as_ref(&self) -> &Gc {
+        self
     }
 }
 
@@ -205,6 +1162,39 @@ impl AsRef<[u8]> for Gc {
     }
 }
 
+/**
+`Gc` hashes and compares exactly as the `str` it contains, so a `Gc` can be used to look up `str` keys in hashed and ordered collections.
+*/
+impl Borrow<str> for Gc {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/**
+Allows `str` methods to be called directly on a `Gc`.
+
+Where `Gc` has an inherent method of the same name (such as `len`, `chars`, `bytes`, `as_bytes`, or `to_lowercase`), the inherent method is used.
+*/
+impl Deref for Gc {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/**
+Converts a cluster consisting of exactly one code point into that code point.
+
+See [`Gc::try_as_char`](struct.Gc.html#method.try_as_char).
+*/
+impl<'a> TryFrom<&'a Gc> for char {
+    type Error = NotCharError;
+    fn try_from(gc: &'a Gc) -> Result<char, NotCharError> {
+        gc.try_as_char().ok_or(NotCharError)
+    }
+}
+
 impl Debug for Gc {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         Debug::fmt(&self.0, fmt)
@@ -247,18 +1237,21 @@ impl<'a> PartialEq<&'a str> for Gc {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PartialEq<GcBuf> for Gc {
     fn eq(&self, other: &GcBuf) -> bool {
         self.0.eq(other.as_gc())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PartialEq<String> for Gc {
     fn eq(&self, other: &String) -> bool {
         self.0.eq(&**other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<Cow<'a, Gc>> for Gc {
     fn eq(&self, other: &Cow<'a, Gc>) -> bool {
         self.0.eq((*other).deref())
@@ -277,18 +1270,21 @@ impl<'a> PartialEq<str> for &'a Gc {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<GcBuf> for &'a Gc {
     fn eq(&self, other: &GcBuf) -> bool {
         self.0.eq(other.as_gc())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<String> for &'a Gc {
     fn eq(&self, other: &String) -> bool {
         self.0.eq(&**other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<Cow<'a, Gc>> for &'a Gc {
     fn eq(&self, other: &Cow<'a, Gc>) -> bool {
         self.0.eq((*other).deref())
@@ -313,12 +1309,14 @@ impl<'a> PartialEq<Gc> for &'a str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PartialEq<Gc> for String {
     fn eq(&self, other: &Gc) -> bool {
         self.eq(&other.as_str())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<Gc> for Cow<'a, Gc> {
     fn eq(&self, other: &Gc) -> bool {
         (**self).eq(other)
@@ -337,12 +1335,14 @@ impl<'a> PartialEq<&'a Gc> for str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<&'a Gc> for String {
     fn eq(&self, other: &&'a Gc) -> bool {
         self.eq(&other.as_str())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialEq<&'a Gc> for Cow<'a, Gc> {
     fn eq(&self, other: &&'a Gc) -> bool {
         (**self).eq(*other)
@@ -386,18 +1386,21 @@ impl<'a> PartialOrd<&'a str> for Gc {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PartialOrd<GcBuf> for Gc {
     fn partial_cmp(&self, other: &GcBuf) -> Option<Ordering> {
         self.0.partial_cmp(other.as_gc())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PartialOrd<String> for Gc {
     fn partial_cmp(&self, other: &String) -> Option<Ordering> {
         self.0.partial_cmp(&**other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<Cow<'a, Gc>> for Gc {
     fn partial_cmp(&self, other: &Cow<'a, Gc>) -> Option<Ordering> {
         self.0.partial_cmp((*other).deref())
@@ -416,18 +1419,21 @@ impl<'a> PartialOrd<str> for &'a Gc {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<GcBuf> for &'a Gc {
     fn partial_cmp(&self, other: &GcBuf) -> Option<Ordering> {
         self.0.partial_cmp(other.as_gc())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<String> for &'a Gc {
     fn partial_cmp(&self, other: &String) -> Option<Ordering> {
         self.0.partial_cmp(&**other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<Cow<'a, Gc>> for &'a Gc {
     fn partial_cmp(&self, other: &Cow<'a, Gc>) -> Option<Ordering> {
         self.0.partial_cmp((*other).deref())
@@ -452,12 +1458,14 @@ impl<'a> PartialOrd<Gc> for &'a str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PartialOrd<Gc> for String {
     fn partial_cmp(&self, other: &Gc) -> Option<Ordering> {
         (&**self).partial_cmp(other.as_str())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<Gc> for Cow<'a, Gc> {
     fn partial_cmp(&self, other: &Gc) -> Option<Ordering> {
         (**self).partial_cmp(other)
@@ -476,18 +1484,21 @@ impl<'a> PartialOrd<&'a Gc> for str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<&'a Gc> for String {
     fn partial_cmp(&self, other: &&'a Gc) -> Option<Ordering> {
         (&**self).partial_cmp(other.as_str())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> PartialOrd<&'a Gc> for Cow<'a, Gc> {
     fn partial_cmp(&self, other: &&'a Gc) -> Option<Ordering> {
         (**self).partial_cmp(*other)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl ToOwned for Gc {
     type Owned = GcBuf;
     fn to_owned(&self) -> Self::Owned {
@@ -500,170 +1511,512 @@ impl ToOwned for Gc {
 /**
 An owned, single Unicode grapheme cluster (akin to `String`).
 
-See [`Gc`](struct.Gc.html) for more details.
-*/
-#[cfg(has_string_into_boxed_string)]
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct GcBuf(Box<str>);
+See [`Gc`](struct.Gc.html) for more details.
+
+# Memory
+
+A `GcBuf` is always exactly sized: it stores the bytes of the cluster and nothing more, so there is no spare capacity to reclaim, and no `capacity` or `shrink_to_fit` method.  In exchange, methods which change the length of the cluster, such as [`pop_mark`](#method.pop_mark) and [`set_base`](#method.set_base), allocate a new buffer each time they are called.  When building up a cluster a code point at a time, it is cheaper to collect the code points into a `String` first, and convert it once with `GcBuf::try_from` or [`GcBuf::try_from_chars`](#method.try_from_chars).
+
+Requires the `alloc` feature.
+*/
+#[cfg(feature = "alloc")]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GcBuf(Box<str>);
+
+#[cfg(feature = "alloc")]
+impl GcBuf {
+    /**
+    Create a new `GcBuf` from the given `String`.
+
+    This function *does not* check to ensure the provided string is a single, valid grapheme cluster.
+    */
+    pub unsafe fn from_string_unchecked(s: String) -> GcBuf {
+        GcBuf(s.into_boxed_str())
+    }
+
+    /**
+    Create a new `GcBuf` from a sequence of code points, such as a base code point followed by its marks.
+
+    Returns an error if the code points do not form exactly one grapheme cluster.
+    */
+    pub fn from_chars<I>(chars: I) -> Result<GcBuf, GcParseError>
+    where I: IntoIterator<Item=char> {
+        let s: String = chars.into_iter().collect();
+        check_single_gc(&s)?;
+        Ok(unsafe { GcBuf::from_string_unchecked(s) })
+    }
+
+    /**
+    Create a new `GcBuf` from a base code point followed by a string of marks.
+
+    Returns an error if the result is not exactly one grapheme cluster; for example, if `base` cannot take marks, or `marks` contains a code point which does not extend the cluster.  The error indicates where the cluster breaks.
+    */
+    pub fn compose(base: char, marks: &str) -> Result<GcBuf, ComposeError> {
+        let mut s = String::with_capacity(base.len_utf8() + marks.len());
+        s.push(base);
+        s.push_str(marks);
+        compose_string(s)
+    }
+
+    /**
+    Create a new `GcBuf` from a sequence of code points.
+
+    This is the same as [`from_chars`](#method.from_chars), except that on failure, the error indicates where the cluster breaks.
+    */
+    pub fn try_from_chars<I>(chars: I) -> Result<GcBuf, ComposeError>
+    where I: IntoIterator<Item=char> {
+        compose_string(chars.into_iter().collect())
+    }
+
+    /**
+    Returns a borrowed grapheme cluster slice.
+    */
+    pub fn as_gc(&self) -> &Gc {
+        unsafe {
+            Gc::from_str_unchecked(&self.0)
+        }
+    }
+
+    /**
+    Removes and returns the last code point of this cluster if it is a combining mark (General_Category `M`), or returns `None` otherwise.
+
+    Unlike [`Gc::mark_str`](struct.Gc.html#method.mark_str), this only considers true combining marks: a trailing zero width joiner, emoji modifier, regional indicator or Hangul jamo is left in place, and `None` is returned.  The base code point is never removed, so the cluster always contains at least one code point.  Removing a mark reallocates the buffer to its new, exact size.
+
+    Requires the `unicode-properties` feature.
+    */
+    #[cfg(feature = "unicode-properties")]
+    pub fn pop_mark(&mut self) -> Option<char> {
+        match self.mark_str().chars().next_back() {
+            Some(c) if ::props::is_mark_char(c) => (),
+            _ => return None,
+        }
+        let mut s: String = ::core::mem::take(&mut self.0).into();
+        let mark = s.pop();
+        unsafe {
+            *self = GcBuf::from_string_unchecked(s);
+        }
+        mark
+    }
+
+    /**
+    Replaces the base code point of this cluster with `c`, keeping any marks.
+
+    In rare cases, the new base and the existing marks do not form a single grapheme cluster; for example, if `c` is a control character such as `'\n'`, or if this cluster is a flag and `c` is not a regional indicator.  In that case, an error is returned and the cluster is left unchanged.
+    */
+    pub fn set_base(&mut self, c: char) -> Result<(), GcParseError> {
+        let marks = self.mark_str();
+        let mut s = String::with_capacity(c.len_utf8() + marks.len());
+        s.push(c);
+        s.push_str(marks);
+        check_single_gc(&s)?;
+        unsafe {
+            *self = GcBuf::from_string_unchecked(s);
+        }
+        Ok(())
+    }
+
+    /**
+    Converts the ASCII code points in this cluster to upper case in place, leaving all other code points unchanged.
+
+    As with `str::make_ascii_uppercase`, this does not reallocate.  Unlike [`Gc::to_ascii_uppercase`](struct.Gc.html#method.to_ascii_uppercase), clusters which are not entirely ASCII are still affected: `"e\u{301}"` becomes `"E\u{301}"`.
+    */
+    pub fn make_ascii_uppercase(&mut self) {
+        // ASCII letters have no special segmentation properties, so changing their case cannot split the cluster.
+        self.0.make_ascii_uppercase();
+    }
+
+    /**
+    Converts the ASCII code points in this cluster to lower case in place, leaving all other code points unchanged.
+
+    As with `str::make_ascii_lowercase`, this does not reallocate.  Unlike [`Gc::to_ascii_lowercase`](struct.Gc.html#method.to_ascii_lowercase), clusters which are not entirely ASCII are still affected: `"E\u{301}"` becomes `"e\u{301}"`.
+    */
+    pub fn make_ascii_lowercase(&mut self) {
+        self.0.make_ascii_lowercase();
+    }
+
+    /**
+    Converts this cluster into the code point it consists of, if it is exactly one code point.
+
+    If the cluster contains more than one code point, the buffer is returned unchanged.  To inspect a cluster without consuming it, use [`Gc::try_as_char`](struct.Gc.html#method.try_as_char).
+    */
+    pub fn into_char(self) -> Result<char, GcBuf> {
+        match self.try_as_char() {
+            Some(c) => Ok(c),
+            None => Err(self),
+        }
+    }
+
+    /**
+    Consumes the `GcBuf`, leaking its contents and returning a `'static` reference to it.
+
+    The memory backing the cluster is *never* freed, so this should only be used for data which must live for the remainder of the program, such as a table of interned grapheme clusters built at runtime.  Calling this repeatedly will steadily leak memory.
+
+    This is the equivalent of `Box::leak`.
+    */
+    pub fn leak(self) -> &'static Gc {
+        unsafe {
+            Gc::from_str_unchecked(Box::leak(self.0))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<Gc> for GcBuf {
+    fn as_ref(&self) -> &Gc {
+        self.as_gc()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<str> for GcBuf {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for GcBuf {
+    fn as_ref(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+}
+
+/**
+`GcBuf` hashes and compares exactly as the `Gc` it contains, so a `Gc` can be used to look up `GcBuf` keys in hashed and ordered collections.
+*/
+#[cfg(feature = "alloc")]
+impl Borrow<Gc> for GcBuf {
+    fn borrow(&self) -> &Gc {
+        self.as_gc()
+    }
+}
+
+/**
+`GcBuf` hashes and compares exactly as the `str` it contains, so a `str` can be used to look up `GcBuf` keys in hashed and ordered collections.
+*/
+#[cfg(feature = "alloc")]
+impl Borrow<str> for GcBuf {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Debug for GcBuf {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, fmt)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for GcBuf {
+    fn default() -> Self {
+        unsafe {
+            GcBuf::from_string_unchecked(String::from("\u{0}"))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deref for GcBuf {
+    type Target = Gc;
+    fn deref(&self) -> &Gc {
+        self.as_gc()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Display for GcBuf {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, fmt)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a Gc> for GcBuf {
+    fn from(v: &'a Gc) -> Self {
+        unsafe {
+            GcBuf::from_string_unchecked(v.as_str().to_owned())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<char> for GcBuf {
+    fn from(v: char) -> Self {
+        let mut buf = [0; 4];
+        unsafe {
+            GcBuf::from_string_unchecked(v.encode_utf8(&mut buf).to_owned())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for GcBuf {
+    type Err = GcParseError;
+    fn from_str(s: &str) -> Result<GcBuf, GcParseError> {
+        check_single_gc(s)?;
+        Ok(unsafe { GcBuf::from_string_unchecked(s.to_owned()) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> TryFrom<&'a str> for GcBuf {
+    type Error = GcParseError;
+    fn try_from(s: &'a str) -> Result<GcBuf, GcParseError> {
+        s.parse()
+    }
+}
+
+/**
+Converts a `String` consisting of exactly one grapheme cluster into a `GcBuf`, without copying.
+
+On failure, the original string is returned inside the error.
+*/
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for GcBuf {
+    type Error = FromStringError;
+    fn try_from(s: String) -> Result<GcBuf, FromStringError> {
+        match check_single_gc(&s) {
+            Ok(()) => Ok(unsafe { GcBuf::from_string_unchecked(s) }),
+            Err(err) => Err(FromStringError::new(s, err))
+        }
+    }
+}
+
+/**
+Converts a cluster consisting of exactly one code point into that code point.
+
+This is the reverse of `From<char> for GcBuf`.  To get the buffer back on failure, use [`GcBuf::into_char`](struct.GcBuf.html#method.into_char) instead.
+*/
+#[cfg(feature = "alloc")]
+impl TryFrom<GcBuf> for char {
+    type Error = NotCharError;
+    fn try_from(gc: GcBuf) -> Result<char, NotCharError> {
+        gc.try_as_char().ok_or(NotCharError)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Into<Box<str>> for GcBuf {
+    fn into(self) -> Box<str> {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Into<String> for GcBuf {
+    fn into(self) -> String {
+        self.0.into_string()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Into<Vec<u8>> for GcBuf {
+    fn into(self) -> Vec<u8> {
+        self.0.into_string().into()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<GcBuf> for Arc<str> {
+    fn from(v: GcBuf) -> Self {
+        Arc::from(v.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<GcBuf> for Rc<str> {
+    fn from(v: GcBuf) -> Self {
+        Rc::from(v.0)
+    }
+}
 
-/**
-An owned, single Unicode grapheme cluster (akin to `String`).
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a Gc> for Box<str> {
+    fn from(v: &'a Gc) -> Self {
+        Box::from(v.as_str())
+    }
+}
 
-See [`Gc`](struct.Gc.html) for more details.
+/*
+The following conversions reinterpret pointers to `str` as pointers to `Gc`, and vice versa.  This is sound because `Gc` is `repr(transparent)` over `str`, so both have the same layout and pointer metadata; it is the same technique `std` uses to convert between `Box<str>` and `Box<[u8]>`.  In each case, the contents are already known to be a single grapheme cluster.
 */
-#[cfg(not(has_string_into_boxed_string))]
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct GcBuf(String);
-
-impl GcBuf {
-    /**
-    Create a new `GcBuf` from the given `String`.
 
-    This function *does not* check to ensure the provided string is a single, valid grapheme cluster.
-    */
-    pub unsafe fn from_string_unchecked(s: String) -> GcBuf {
-        Self::from_string_unchecked_impl(s)
+#[cfg(feature = "alloc")]
+impl From<GcBuf> for Box<Gc> {
+    fn from(v: GcBuf) -> Self {
+        unsafe { Box::from_raw(Box::into_raw(v.0) as *mut Gc) }
     }
+}
 
-    #[cfg(has_string_into_boxed_string)]
-    unsafe fn from_string_unchecked_impl(s: String) -> GcBuf {
-        GcBuf(s.into_boxed_str())
+#[cfg(feature = "alloc")]
+impl From<Box<Gc>> for GcBuf {
+    fn from(v: Box<Gc>) -> Self {
+        GcBuf(unsafe { Box::from_raw(Box::into_raw(v) as *mut str) })
     }
+}
 
-    #[cfg(not(has_string_into_boxed_string))]
-    unsafe fn from_string_unchecked_impl(s: String) -> GcBuf {
-        GcBuf(s)
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a Gc> for Rc<Gc> {
+    fn from(v: &'a Gc) -> Self {
+        let rc: Rc<str> = Rc::from(v.as_str());
+        unsafe { Rc::from_raw(Rc::into_raw(rc) as *const Gc) }
     }
+}
 
-    /**
-    Returns a borrowed grapheme cluster slice.
-    */
-    pub fn as_gc(&self) -> &Gc {
-        unsafe {
-            Gc::from_str_unchecked(&self.0)
-        }
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a Gc> for Arc<Gc> {
+    fn from(v: &'a Gc) -> Self {
+        let arc: Arc<str> = Arc::from(v.as_str());
+        unsafe { Arc::from_raw(Arc::into_raw(arc) as *const Gc) }
     }
 }
 
-impl AsRef<Gc> for GcBuf {
-    fn as_ref(&self) -> &Gc {
-        self.as_gc()
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a Gc> for Cow<'a, Gc> {
+    fn from(v: &'a Gc) -> Self {
+        Cow::Borrowed(v)
     }
 }
 
-impl AsRef<str> for GcBuf {
-    fn as_ref(&self) -> &str {
-        self.as_str()
+#[cfg(feature = "alloc")]
+impl<'a> From<GcBuf> for Cow<'a, Gc> {
+    fn from(v: GcBuf) -> Self {
+        Cow::Owned(v)
     }
 }
 
-impl AsRef<[u8]> for GcBuf {
-    fn as_ref(&self) -> &[u8] {
-        self.as_str().as_bytes()
+#[cfg(feature = "alloc")]
+impl<'a> FromIterator<&'a Gc> for String {
+    fn from_iter<I>(iter: I) -> String
+    where I: IntoIterator<Item=&'a Gc> {
+        let mut s = String::new();
+        s.extend(iter);
+        s
     }
 }
 
-impl Borrow<Gc> for GcBuf {
-    fn borrow(&self) -> &Gc {
-        self.as_gc()
+#[cfg(feature = "alloc")]
+impl FromIterator<GcBuf> for String {
+    fn from_iter<I>(iter: I) -> String
+    where I: IntoIterator<Item=GcBuf> {
+        let mut s = String::new();
+        s.extend(iter);
+        s
     }
 }
 
-impl Debug for GcBuf {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        Debug::fmt(&self.0, fmt)
+#[cfg(feature = "alloc")]
+impl<'a> FromIterator<&'a Gc> for Cow<'a, str> {
+    fn from_iter<I>(iter: I) -> Cow<'a, str>
+    where I: IntoIterator<Item=&'a Gc> {
+        Cow::Owned(iter.into_iter().collect())
     }
 }
 
-impl Default for GcBuf {
-    fn default() -> Self {
-        unsafe {
-            GcBuf::from_string_unchecked(String::from("\u{0}"))
+#[cfg(feature = "alloc")]
+impl<'a> Extend<&'a Gc> for String {
+    fn extend<I>(&mut self, iter: I)
+    where I: IntoIterator<Item=&'a Gc> {
+        let iter = iter.into_iter();
+        // Every cluster is at least one byte long.
+        self.reserve(iter.size_hint().0);
+        for gc in iter {
+            self.push_str(gc.as_str());
         }
     }
 }
 
-impl Deref for GcBuf {
-    type Target = Gc;
-    fn deref(&self) -> &Gc {
-        self.as_gc()
+#[cfg(feature = "alloc")]
+impl Extend<GcBuf> for String {
+    fn extend<I>(&mut self, iter: I)
+    where I: IntoIterator<Item=GcBuf> {
+        let iter = iter.into_iter();
+        // Every cluster is at least one byte long.
+        self.reserve(iter.size_hint().0);
+        for gc in iter {
+            self.push_str(gc.as_str());
+        }
     }
 }
 
-impl Display for GcBuf {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        Display::fmt(&self.0, fmt)
-    }
+/**
+A grapheme cluster consisting of a single code point, stored inline without allocating.
+
+Since a single code point is always exactly one grapheme cluster, this can be constructed from any `char`.  It dereferences to [`Gc`](struct.Gc.html), so all of the usual cluster methods are available.
+*/
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GcInline {
+    buf: [u8; 4],
+    len: u8,
 }
 
-impl<'a> From<&'a Gc> for GcBuf {
-    fn from(v: &'a Gc) -> Self {
+impl GcInline {
+    /**
+    Create a new inline grapheme cluster from a single code point.
+    */
+    #[inline]
+    pub fn from_char(c: char) -> GcInline {
+        let mut buf = [0; 4];
+        let len = c.encode_utf8(&mut buf).len() as u8;
+        GcInline { buf, len }
+    }
+
+    /**
+    Returns a borrowed grapheme cluster slice.
+    */
+    #[inline]
+    pub fn as_gc(&self) -> &Gc {
         unsafe {
-            GcBuf::from_string_unchecked(v.as_str().to_owned())
+            Gc::from_str_unchecked(::core::str::from_utf8_unchecked(&self.buf[..self.len as usize]))
         }
     }
 }
 
-impl From<char> for GcBuf {
-    fn from(v: char) -> Self {
-        unsafe {
-            let mut buf = [0; 4];
-            let bs = match ::util::encode_utf8_raw(v as u32, &mut buf) {
-                Some(len) => {
-                    if len < 4 {
-                        &buf[..len]
-                    } else {
-                        debug_unreachable!();
-                    }
-                },
-                None => debug_unreachable!(),
-            };
-            let s: &str = transmute(bs);
-            let s = s.to_owned();
-            GcBuf::from_string_unchecked(s)
-        }
+impl AsRef<Gc> for GcInline {
+    fn as_ref(&self) -> &Gc {
+        self.as_gc()
     }
 }
 
-#[cfg(has_string_into_boxed_string)]
-impl Into<Box<str>> for GcBuf {
-    fn into(self) -> Box<str> {
-        self.0
+impl AsRef<str> for GcInline {
+    fn as_ref(&self) -> &str {
+        self.as_gc().as_str()
     }
 }
 
-#[cfg(has_string_into_boxed_string)]
-impl Into<String> for GcBuf {
-    fn into(self) -> String {
-        self.0.into_string()
+impl Debug for GcInline {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.as_gc(), fmt)
     }
 }
 
-#[cfg(has_string_into_boxed_string)]
-impl Into<Vec<u8>> for GcBuf {
-    fn into(self) -> Vec<u8> {
-        self.0.into_string().into()
+impl Deref for GcInline {
+    type Target = Gc;
+    fn deref(&self) -> &Gc {
+        self.as_gc()
     }
 }
 
-#[cfg(not(has_string_into_boxed_string))]
-impl Into<String> for GcBuf {
-    fn into(self) -> String {
-        self.0
+impl Display for GcInline {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self.as_gc(), fmt)
     }
 }
 
-#[cfg(not(has_string_into_boxed_string))]
-impl Into<Vec<u8>> for GcBuf {
-    fn into(self) -> Vec<u8> {
-        self.0.into()
+impl From<char> for GcInline {
+    fn from(v: char) -> Self {
+        GcInline::from_char(v)
     }
 }
 
+#[cfg(feature = "alloc")]
 macro_rules! as_item {
     ($i:item) => { $i };
 }
 
+#[cfg(feature = "alloc")]
 macro_rules! forward_partial_eq {
     (~ <$lt:tt> $lhs:ty, $rhs:ty) => {
         as_item! {
@@ -702,20 +2055,33 @@ macro_rules! forward_partial_eq {
     };
 }
 
+#[cfg(feature = "alloc")]
 forward_partial_eq! { GcBuf, char }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { GcBuf, str }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { GcBuf, Gc }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { GcBuf, String }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { <'a> GcBuf, &'a str }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { <'a> GcBuf, &'a Gc }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { <'a> GcBuf, Cow<'a, Gc> }
 
+#[cfg(feature = "alloc")]
 forward_partial_eq! { ~ char, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { ~ str, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { ~ String, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { ~ <'a> &'a str, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_eq! { ~ <'a> Cow<'a, Gc>, GcBuf }
 
+#[cfg(feature = "alloc")]
 macro_rules! forward_partial_ord {
     (~ <$lt:tt> $lhs:ty, $rhs:ty) => {
         as_item! {
@@ -754,23 +2120,43 @@ macro_rules! forward_partial_ord {
     };
 }
 
+#[cfg(feature = "alloc")]
 forward_partial_ord! { GcBuf, char }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { GcBuf, str }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { GcBuf, Gc }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { GcBuf, String }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { <'a> GcBuf, &'a str }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { <'a> GcBuf, &'a Gc }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { <'a> GcBuf, Cow<'a, Gc> }
 
+#[cfg(feature = "alloc")]
 forward_partial_ord! { ~ char, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { ~ str, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { ~ String, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { ~ <'a> &'a str, GcBuf }
+#[cfg(feature = "alloc")]
 forward_partial_ord! { ~ <'a> Cow<'a, Gc>, GcBuf }
 
 #[cfg(test)]
 mod gc_tests {
+    #[cfg(feature = "alloc")] use std::borrow::ToOwned;
+    #[cfg(feature = "alloc")] use std::boxed::Box;
+    #[cfg(feature = "alloc")] use std::rc::Rc;
+    use std::string::String;
+    #[cfg(feature = "alloc")] use std::string::ToString;
+    #[cfg(feature = "alloc")] use std::sync::Arc;
+    use std::vec::Vec;
     use super::Gc;
+    #[cfg(feature = "alloc")] use super::GcBuf;
 
     fn gc(s: &str) -> &Gc {
         Gc::from_str(s).unwrap()
@@ -802,6 +2188,38 @@ mod gc_tests {
         assert_eq!(Gc::split_from("ab").map(map), Some(("a", "b")));
     }
 
+    #[test]
+    fn test_rsplit_from() {
+        fn map<'a>((s, gr): (&'a str, &'a Gc)) -> (&'a str, &'a str) {
+            (s, gr.as_str())
+        }
+
+        assert_eq!(Gc::rsplit_from("a").map(map), Some(("", "a")));
+        assert_eq!(Gc::rsplit_from("á").map(map), Some(("", "á")));
+        assert_eq!(Gc::rsplit_from("ä").map(map), Some(("", "ä")));
+        assert_eq!(Gc::rsplit_from("̈").map(map), Some(("", "̈"))); // NB: there is a single combining diaereses in the string.
+        assert_eq!(Gc::rsplit_from("字").map(map), Some(("", "字")));
+        assert_eq!(Gc::rsplit_from("").map(map), None);
+        assert_eq!(Gc::rsplit_from("ab").map(map), Some(("a", "b")));
+        assert_eq!(Gc::rsplit_from("x\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}").map(map),
+            Some(("x", "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}")));
+        assert_eq!(Gc::rsplit_from("a\r\n").map(map), Some(("a", "\r\n")));
+
+        assert_eq!(Gc::first_in("e\u{301}x").map(Gc::as_str), Some("e\u{301}"));
+        assert_eq!(Gc::last_in("xe\u{301}").map(Gc::as_str), Some("e\u{301}"));
+        assert_eq!(Gc::first_in(""), None);
+        assert_eq!(Gc::last_in(""), None);
+    }
+
+    #[test]
+    fn test_byte_len_and_char_count() {
+        assert_eq!((gc("a").byte_len(), gc("a").char_count()), (1, 1));
+        assert_eq!((gc("á").byte_len(), gc("á").char_count()), (2, 1));
+        assert_eq!((gc("a\u{308}").byte_len(), gc("a\u{308}").char_count()), (3, 2));
+        assert_eq!((gc("字").byte_len(), gc("字").char_count()), (3, 1));
+        assert_eq!(gc("a\u{308}").byte_len(), gc("a\u{308}").len());
+    }
+
     #[test]
     fn test_has_marks() {
         assert!(!gc("a").has_marks());
@@ -820,6 +2238,916 @@ mod gc_tests {
         assert_eq!(gc("字").base_char(), '字');
     }
 
+    #[test]
+    fn test_base_is() {
+        assert!(gc("a").base_is('a'));
+        assert!(gc("a\u{308}").base_is('a'));
+        assert!(!gc("\u{e4}").base_is('a'));
+        assert!(!gc("b").base_is('a'));
+        assert!(gc("a") == 'a');
+        assert!(gc("a\u{308}") != 'a');
+    }
+
+    #[cfg(feature = "width")]
+    #[test]
+    fn test_width() {
+        assert_eq!(gc("a").width(), 1);
+        assert_eq!(gc("漢").width(), 2);
+        assert_eq!(gc("\u{e9}").width(), 1);
+        assert_eq!(gc("e\u{301}").width(), 1);
+        assert_eq!(gc("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}").width(), 2);
+        assert_eq!(gc("\u{1F1E6}\u{1F1FA}").width(), 2);
+        assert_eq!(gc("\u{200D}").width(), 0);
+        assert_eq!(gc("\t").width(), 0);
+        assert_eq!(gc("\r\n").width(), 0);
+    }
+
+    #[test]
+    fn test_base() {
+        assert_eq!(gc("a\u{308}").base(), gc("a"));
+        assert_eq!(gc("a\u{308}").strip_marks(), gc("a"));
+        assert_eq!(gc("a\u{323}\u{302}").base(), gc("a"));
+        assert_eq!(gc("\r\n").base(), gc("\r"));
+        assert_eq!(gc("\u{1F4AA}\u{1F3FD}").strip_marks(), gc("\u{1F4AA}"));
+
+        // Mark-free clusters are returned unchanged.
+        let g = gc("\u{1F4AA}");
+        assert_eq!(g.base().as_str().as_ptr(), g.as_str().as_ptr());
+        assert_eq!(g.base(), g);
+
+        // The result is always a single cluster, even with several marks.
+        let g = gc("e\u{301}\u{302}\u{303}");
+        assert_eq!(Gc::from_str(g.base().as_str()), Some(gc("e")));
+        assert_eq!(g.base().mark_str(), "");
+    }
+
+    #[test]
+    fn test_try_as_char() {
+        use std::convert::TryFrom;
+        use NotCharError;
+        assert_eq!(gc("a").try_as_char(), Some('a'));
+        assert_eq!(gc("\u{E9}").try_as_char(), Some('\u{E9}'));
+        assert_eq!(gc("\u{1F4AA}").try_as_char(), Some('\u{1F4AA}'));
+        assert_eq!(gc("e\u{301}").try_as_char(), None);
+        assert_eq!(gc("\r\n").try_as_char(), None);
+        assert_eq!(gc("\u{1F4AA}\u{1F3FD}").try_as_char(), None);
+        assert_eq!(char::try_from(gc("a")), Ok('a'));
+        assert_eq!(char::try_from(gc("e\u{301}")), Err(NotCharError));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_into_char() {
+        assert_eq!(GcBuf::from('a').into_char(), Ok('a'));
+        assert_eq!(GcBuf::from('\u{1F4AA}').into_char(), Ok('\u{1F4AA}'));
+        let marked = GcBuf::from(gc("\u{1F4AA}\u{1F3FD}"));
+        assert_eq!(marked.clone().into_char(), Err(marked));
+        let b = GcBuf::from(gc("e\u{301}\u{302}"));
+        let back = b.into_char().unwrap_err();
+        assert_eq!(back, "e\u{301}\u{302}");
+        assert_eq!(back.as_bytes(), "e\u{301}\u{302}".as_bytes());
+
+        use std::convert::TryFrom;
+        use NotCharError;
+        assert_eq!(char::try_from(GcBuf::from('\u{1F4AA}')), Ok('\u{1F4AA}'));
+        assert_eq!(char::try_from(GcBuf::from(gc("e\u{301}"))), Err(NotCharError));
+        assert_eq!(char::try_from(GcBuf::from(gc("\r\n"))), Err(NotCharError));
+        for &c in &['a', '\u{E9}', '\u{20AC}', '\u{301}'] {
+            assert_eq!(char::try_from(GcBuf::from(c)), Ok(c));
+        }
+    }
+
+    #[test]
+    fn test_try_as_ascii_byte() {
+        assert_eq!(gc("a").try_as_ascii_byte(), Some(b'a'));
+        assert_eq!(gc("7").try_as_ascii_byte(), Some(b'7'));
+        assert_eq!(gc("\x7F").try_as_ascii_byte(), Some(0x7F));
+        assert_eq!(gc("\u{E9}").try_as_ascii_byte(), None);
+        assert_eq!(gc("\u{E4}").try_as_ascii_byte(), None);
+        assert_eq!(gc("a\u{308}").try_as_ascii_byte(), None);
+        assert_eq!(gc("\r\n").try_as_ascii_byte(), None);
+        assert_eq!(gc("\u{1F4AA}").try_as_ascii_byte(), None);
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_compose_char() {
+        assert_eq!(gc("a").compose_char(), Some('a'));
+        assert_eq!(gc("\u{E9}").compose_char(), Some('\u{E9}'));
+        assert_eq!(gc("e\u{301}").compose_char(), Some('\u{E9}'));
+        assert_eq!(gc("e\u{323}\u{302}").compose_char(), Some('\u{1EC7}'));
+        assert_eq!(gc("\u{1112}\u{1161}\u{11AB}").compose_char(), Some('\u{D55C}'));
+        assert_eq!(gc("x\u{301}").compose_char(), None);
+        assert_eq!(gc("e\u{301}\u{301}").compose_char(), None);
+        assert_eq!(gc("\r\n").compose_char(), None);
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_is_mark_char() {
+        use is_mark_char;
+        assert!(is_mark_char('\u{301}'));
+        assert!(is_mark_char('\u{903}'));
+        assert!(is_mark_char('\u{20DD}'));
+        assert!(!is_mark_char('a'));
+        assert!(!is_mark_char('\u{200D}'));
+        assert!(is_mark_char('\u{FE0F}'));
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_base_category() {
+        use GeneralCategory::*;
+        assert_eq!(gc("e\u{301}").base_category(), Ll);
+        assert!(gc("e\u{301}").is_letter());
+        assert!(gc("\u{6F22}").is_letter());
+        assert!(!gc("7").is_letter());
+        assert_eq!(gc("7").base_category(), Nd);
+        assert!(gc("\u{308}").is_mark_only());
+        assert!(gc("\u{301}\u{302}").is_mark_only());
+        assert!(!gc("a\u{308}").is_mark_only());
+        assert!(gc("!").is_punctuation());
+        assert!(gc("\u{AB}").is_punctuation());
+        assert!(!gc("+").is_punctuation());
+        assert!(gc("+").is_symbol());
+        assert!(gc("$").is_symbol());
+        assert!(gc("\u{1F44D}\u{1F3FD}").is_symbol());
+        assert!(!gc(" ").is_symbol());
+        assert_eq!(gc(" ").base_category(), Zs);
+        assert_eq!(gc("\r\n").base_category(), Cc);
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_script() {
+        use Script;
+        assert_eq!(gc("a").script(), Script::Latin);
+        assert_eq!(gc("e\u{301}").script(), Script::Latin);
+        assert_eq!(gc("\u{6F22}").script(), Script::Han);
+        assert_eq!(gc("\u{306E}").script(), Script::Hiragana);
+        assert_eq!(gc("\u{30C6}").script(), Script::Katakana);
+        assert_eq!(gc("\u{3B1}").script(), Script::Greek);
+        assert_eq!(gc("\u{5D0}").script(), Script::Hebrew);
+        assert_eq!(gc("\u{D55C}").script(), Script::Hangul);
+        assert_eq!(gc("7").script(), Script::Common);
+        assert_eq!(gc("\u{1F44D}\u{1F3FD}").script(), Script::Common);
+        assert_eq!(gc("\u{308}").script(), Script::Inherited);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_escape() {
+        let family = gc("\u{1F468}\u{200D}\u{1F469}");
+        assert_eq!(family.escape_debug(), "\u{1F468}\\u{200d}\u{1F469}");
+        assert_eq!(family.escape_unicode(), "\\u{1f468}\\u{200d}\\u{1f469}");
+        assert_eq!(format!("<{}>", family.display_escaped()), "<\u{1F468}\\u{200d}\u{1F469}>");
+
+        let e = gc("e\u{301}");
+        assert_eq!(e.escape_debug(), "e\\u{301}");
+        assert_eq!(e.escape_unicode(), "\\u{65}\\u{301}");
+        assert_eq!(e.display_escaped().to_string(), "e\\u{301}");
+
+        assert_eq!(gc("\r\n").escape_debug(), "\\r\\n");
+        assert_eq!(gc("\u{2665}\u{FE0F}").escape_debug(), "\u{2665}\\u{fe0f}");
+    }
+
+    #[test]
+    fn test_emoji_scans() {
+        assert!(gc("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}").has_zwj());
+        assert!(!gc("\u{1F44D}\u{1F3FD}").has_zwj());
+        assert!(!gc("a").has_zwj());
+        assert!(gc("\u{2665}\u{FE0F}").has_variation_selector());
+        assert!(gc("\u{2665}\u{FE0E}").has_variation_selector());
+        assert!(gc("#\u{FE0F}\u{20E3}").has_variation_selector());
+        assert!(gc("\u{8FBB}\u{E0100}").has_variation_selector());
+        assert!(!gc("\u{2665}").has_variation_selector());
+        assert!(!gc("\u{1F1E6}\u{1F1FA}").has_variation_selector());
+    }
+
+    #[test]
+    fn test_zwj_parts() {
+        let family = gc("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}");
+        assert_eq!(family.zwj_parts().collect::<Vec<_>>(),
+            vec!["\u{1F468}", "\u{1F469}", "\u{1F467}", "\u{1F466}"]);
+        assert_eq!(family.zwj_parts().count(), 4);
+
+        let couple = gc("\u{1F469}\u{1F3FD}\u{200D}\u{2764}\u{FE0F}\u{200D}\u{1F468}\u{1F3FB}");
+        assert_eq!(couple.zwj_parts().collect::<Vec<_>>(),
+            vec!["\u{1F469}\u{1F3FD}", "\u{2764}\u{FE0F}", "\u{1F468}\u{1F3FB}"]);
+
+        assert_eq!(gc("\u{1F44D}\u{1F3FD}").zwj_parts().collect::<Vec<_>>(), vec!["\u{1F44D}\u{1F3FD}"]);
+        assert_eq!(gc("e\u{301}").zwj_parts().collect::<Vec<_>>(), vec!["e\u{301}"]);
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_is_emoji() {
+        assert!(gc("\u{1F44D}").is_emoji());
+        assert!(gc("\u{1F44D}\u{1F3FD}").is_emoji());
+        assert!(gc("\u{1F1E6}\u{1F1FA}").is_emoji());
+        assert!(!gc("\u{1F1E6}").is_emoji());
+        assert!(gc("#\u{FE0F}\u{20E3}").is_emoji());
+        assert!(gc("1\u{20E3}").is_emoji());
+        assert!(gc("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}").is_emoji());
+        assert!(gc("\u{2665}\u{FE0F}").is_emoji());
+        assert!(!gc("\u{2665}").is_emoji());
+        assert!(!gc("\u{1F44D}\u{FE0E}").is_emoji());
+        assert!(!gc("#").is_emoji());
+        assert!(!gc("a").is_emoji());
+        assert!(!gc("a\u{20E3}").is_emoji());
+        assert!(!gc("e\u{301}").is_emoji());
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_is_extended_pictographic() {
+        assert!(gc("\u{A9}").is_extended_pictographic());
+        assert!(gc("\u{2604}").is_extended_pictographic());
+        // Unicode 17.0 removed pictographs which are not used as emoji, such as U+2605 BLACK STAR.
+        assert!(!gc("\u{2605}").is_extended_pictographic());
+        assert!(gc("\u{263A}\u{FE0F}").is_extended_pictographic());
+        assert!(gc("\u{1F4AA}\u{1F3FD}").is_extended_pictographic());
+        assert!(gc("\u{1F468}\u{200D}\u{1F469}").is_extended_pictographic());
+        assert!(gc("\u{1FFFD}").is_extended_pictographic());
+        assert!(!gc("a").is_extended_pictographic());
+        assert!(!gc("#\u{FE0F}\u{20E3}").is_extended_pictographic());
+        assert!(!gc("\u{1F1E6}\u{1F1FA}").is_extended_pictographic());
+        assert!(!gc("\u{1F3FD}").is_extended_pictographic());
+        assert!(!gc("\u{1FFFE}").is_extended_pictographic());
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_normalization() {
+        let composed = gc("\u{E9}");
+        let decomposed = gc("e\u{301}");
+        assert_eq!(composed.nfd(), "e\u{301}");
+        assert_eq!(decomposed.nfc(), "\u{E9}");
+        assert!(composed.canonical_eq(decomposed));
+        assert!(decomposed.canonical_eq(composed));
+        assert!(!composed.canonical_eq(gc("e")));
+        assert!(!decomposed.canonical_eq(gc("e\u{300}")));
+
+        // Marks are reordered by combining class.
+        assert!(gc("a\u{323}\u{302}").canonical_eq(gc("a\u{302}\u{323}")));
+        assert_eq!(gc("a\u{302}\u{323}").nfc(), "\u{1EAD}");
+
+        // Hangul syllables and conjoining jamo.
+        let syllable = gc("\u{D55C}");
+        let jamo = gc("\u{1112}\u{1161}\u{11AB}");
+        assert_eq!(syllable.nfd(), jamo);
+        assert_eq!(jamo.nfc(), syllable);
+        assert!(syllable.canonical_eq(jamo));
+        assert!(!syllable.canonical_eq(gc("\u{1112}\u{1161}")));
+
+        // Already in NFC.
+        assert_eq!(gc("x").nfc(), "x");
+        assert_eq!(gc("x").nfd(), "x");
+        assert_eq!(gc("\u{1F468}\u{200D}\u{1F469}").nfc(), "\u{1F468}\u{200D}\u{1F469}");
+        assert!(gc("\r\n").canonical_eq(gc("\r\n")));
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_all_marks_are_ordered() {
+        assert!(gc("a").all_marks_are_ordered());
+        assert!(gc("a\u{323}\u{302}").all_marks_are_ordered());
+        assert!(!gc("a\u{302}\u{323}").all_marks_are_ordered());
+        assert!(gc("a\u{302}\u{323}").nfd().all_marks_are_ordered());
+        // Marks of the same class keep their relative order.
+        assert!(gc("a\u{301}\u{300}").all_marks_are_ordered());
+        assert!(gc("\u{1F468}\u{200D}\u{1F469}").all_marks_are_ordered());
+        assert!(!gc("\u{302}\u{323}").all_marks_are_ordered());
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_cmp_canonical() {
+        use std::cmp::Ordering;
+        let composed = gc("\u{E9}");
+        let decomposed = gc("e\u{301}");
+        assert_eq!(composed.cmp_canonical(decomposed), Ordering::Equal);
+        assert_eq!(decomposed.cmp_canonical(composed), Ordering::Equal);
+        assert_eq!(gc("a\u{323}\u{302}").cmp_canonical(gc("a\u{302}\u{323}")), Ordering::Equal);
+        assert_eq!(gc("\u{D55C}").cmp_canonical(gc("\u{1112}\u{1161}\u{11AB}")), Ordering::Equal);
+
+        // Different clusters are still ordered consistently, regardless of form.
+        assert_eq!(decomposed.cmp_canonical(gc("e\u{308}")), Ordering::Less);
+        assert_eq!(gc("e\u{308}").cmp_canonical(composed), Ordering::Greater);
+        assert_eq!(gc("e").cmp_canonical(decomposed), Ordering::Less);
+
+        let mut v = vec![gc("\u{EB}"), decomposed, gc("e"), composed, gc("e\u{308}")];
+        v.sort_by(|a, b| a.cmp_canonical(b));
+        v.dedup_by(|a, b| a.canonical_eq(b));
+        assert_eq!(v.iter().map(|gc| gc.as_str()).collect::<Vec<_>>(), vec!["e", "e\u{301}", "\u{EB}"]);
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_general_category() {
+        use GeneralCategory::*;
+        assert_eq!(gc("A").general_category(), Lu);
+        assert_eq!(gc("e\u{301}").general_category(), Ll);
+        assert_eq!(gc("7").general_category(), Nd);
+        assert_eq!(gc("\u{301}").general_category(), Mn);
+        assert_eq!(gc(" ").general_category(), Zs);
+        assert_eq!(gc("\r\n").general_category(), Cc);
+        assert_eq!(gc("漢").general_category(), Lo);
+        assert_eq!(gc("\u{1F4AA}\u{1F3FD}").general_category(), So);
+    }
+
+    #[test]
+    fn test_base_len() {
+        assert_eq!(gc("a").base_len(), 1);
+        assert_eq!(gc("a").marks_offset(), 1);
+        assert_eq!(gc("e\u{301}").base_len(), 1);
+        assert_eq!(gc("e\u{301}").marks_offset(), 1);
+        assert_eq!(&gc("e\u{301}").as_str()[gc("e\u{301}").marks_offset()..], "\u{301}");
+        assert_eq!(gc("\u{1F4AA}\u{1F3FD}").base_len(), 4);
+        assert_eq!(gc("漢").marks_offset(), 3);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_deref_str() {
+        fn takes_str(s: &str) -> usize { s.len() }
+
+        let g = gc("e\u{301}");
+        assert!(g.starts_with('e'));
+        assert!(g.ends_with("\u{301}"));
+        assert_eq!(g.find('\u{301}'), Some(1));
+        assert_eq!(takes_str(g), 3);
+        let b = GcBuf::from(g);
+        assert_eq!(takes_str(&b), 3);
+        assert!(b.contains('\u{301}'));
+
+        // Inherent methods must still take precedence over those on `str`.
+        let lower: super::ToLowercase = g.to_lowercase();
+        assert_eq!(lower.collect::<String>(), "e\u{301}");
+        let upper: super::ToUppercase = b.to_uppercase();
+        assert_eq!(upper.collect::<String>(), "E\u{301}");
+        let len: fn(&Gc) -> usize = Gc::len;
+        assert_eq!(len(g), g.len());
+        let _: ::std::str::Chars = g.chars();
+        let _: ::std::str::Bytes = g.bytes();
+        let _: &[u8] = g.as_bytes();
+        let _: &Gc = b.as_gc();
+    }
+
+    #[test]
+    fn test_decompose() {
+        let (base, marks) = gc("a\u{301}\u{323}").decompose();
+        assert_eq!(base, 'a');
+        assert_eq!(marks.collect::<Vec<_>>(), vec!['\u{301}', '\u{323}']);
+        let (base, mut marks) = gc("\u{1F4AA}").decompose();
+        assert_eq!(base, '\u{1F4AA}');
+        assert_eq!(marks.next(), None);
+        match gc("e\u{301}").decompose() {
+            ('e', marks) => assert_eq!(marks.as_str(), "\u{301}"),
+            _ => panic!("unexpected base"),
+        }
+    }
+
+    #[test]
+    fn test_marks() {
+        let g = gc("a\u{301}\u{323}\u{308}");
+        assert_eq!(g.char_count(), 4);
+        assert_eq!(g.marks().collect::<Vec<_>>(), vec!['\u{301}', '\u{323}', '\u{308}']);
+        assert_eq!(g.mark_indices().collect::<Vec<_>>(),
+            vec![(1, '\u{301}'), (3, '\u{323}'), (5, '\u{308}')]);
+        assert_eq!(g.mark_indices().next_back(), Some((5, '\u{308}')));
+
+        // A Hangul syllable built from conjoining jamo.
+        let g = gc("\u{1100}\u{1161}\u{11A8}");
+        assert_eq!(g.char_count(), 3);
+        assert_eq!(g.marks().collect::<String>(), "\u{1161}\u{11A8}");
+        assert_eq!(g.mark_indices().collect::<Vec<_>>(), vec![(3, '\u{1161}'), (6, '\u{11A8}')]);
+
+        assert_eq!(gc("a").marks().next(), None);
+        assert_eq!(gc("a").mark_indices().next(), None);
+    }
+
+    #[test]
+    fn test_classification() {
+        assert!(gc(" ").is_whitespace());
+        assert!(gc("\r\n").is_whitespace());
+        assert!(!gc(" \u{362}").is_whitespace());
+        assert!(!gc(" \u{362}").is_alphanumeric());
+
+        assert!(gc("e\u{301}").is_alphabetic());
+        assert!(gc("e\u{301}").is_alphanumeric());
+        assert!(!gc("e\u{301}").is_numeric());
+        assert!(!gc("e\u{301}").is_whitespace());
+
+        assert!(gc("7\u{20E3}").is_numeric());
+        assert!(gc("7\u{20E3}").is_alphanumeric());
+        assert!(!gc("7\u{20E3}").is_alphabetic());
+
+        assert!(gc("\r\n").is_control());
+        assert!(!gc("a").is_control());
+
+        assert!(gc("e\u{301}").any_char(|c| c == '\u{301}'));
+        assert!(!gc("e\u{301}").all_chars(char::is_alphabetic));
+        assert!(gc("e\u{301}").all_chars(|c| c != 'x'));
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert!(gc("A").is_ascii());
+        assert_eq!(gc("A").to_ascii_char(), Some(b'A'));
+        assert!(!gc("\u{e9}").is_ascii());
+        assert_eq!(gc("\u{e9}").to_ascii_char(), None);
+        assert!(!gc("a\u{308}").is_ascii());
+        assert_eq!(gc("a\u{308}").to_ascii_char(), None);
+        assert!(!gc("\r\n").is_ascii());
+
+        assert!(gc("A").eq_ignore_ascii_case(gc("a")));
+        assert!(gc("a").eq_ignore_ascii_case(gc("a")));
+        assert!(!gc("a").eq_ignore_ascii_case(gc("b")));
+        assert!(!gc("\u{e9}").eq_ignore_ascii_case(gc("\u{c9}")));
+        assert!(gc("a\u{308}").eq_ignore_ascii_case(gc("a\u{308}")));
+        assert!(!gc("a\u{308}").eq_ignore_ascii_case(gc("A\u{308}")));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_ascii_case() {
+        assert_eq!(gc("A").to_ascii_lowercase(), "a");
+        assert_eq!(gc("a").to_ascii_uppercase(), "A");
+        assert_eq!(gc("1").to_ascii_uppercase(), "1");
+        assert_eq!(gc("\u{e9}").to_ascii_uppercase(), "\u{e9}");
+        assert_eq!(gc("A\u{308}").to_ascii_lowercase(), "A\u{308}");
+    }
+
+    #[test]
+    fn test_to_titlecase() {
+        assert_eq!(gc("a").to_titlecase().collect::<String>(), "A");
+        assert_eq!(gc("A").to_titlecase().collect::<String>(), "A");
+        assert_eq!(gc("1").to_titlecase().collect::<String>(), "1");
+        assert_eq!(gc("\u{1F3}").to_titlecase().collect::<String>(), "\u{1F2}");
+        assert_eq!(gc("\u{1F1}").to_titlecase().collect::<String>(), "\u{1F2}");
+        assert_eq!(gc("\u{1C6}\u{301}").to_titlecase().collect::<String>(), "\u{1C5}\u{301}");
+        assert_eq!(gc("\u{FB01}").to_titlecase().collect::<String>(), "Fi");
+        assert_eq!(gc("\u{DF}").to_titlecase().collect::<String>(), "Ss");
+        assert_eq!(gc("\u{1F80}").to_titlecase().collect::<String>(), "\u{1F88}");
+        assert_eq!(gc("\u{1FB7}").to_titlecase().collect::<String>(), "\u{391}\u{342}\u{345}");
+        assert_eq!(gc("e\u{301}").to_titlecase().collect::<String>(), "E\u{301}");
+        // Georgian Mkhedruli title cases to itself, not to Mtavruli.
+        assert_eq!(gc("\u{10D0}").to_titlecase().collect::<String>(), "\u{10D0}");
+        assert_eq!(gc("\u{10FF}").to_titlecase().collect::<String>(), "\u{10FF}");
+        assert_eq!(gc("\u{10D0}").to_uppercase().collect::<String>(), "\u{1C90}");
+        assert_eq!(gc("\u{1C90}").to_titlecase().collect::<String>(), "\u{1C90}");
+        // Marks are passed through, even if they have case mappings.
+        assert_eq!(gc("a\u{345}").to_titlecase().collect::<String>(), "A\u{345}");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_case_str() {
+        use std::borrow::Cow;
+        assert!(matches!(gc("a").to_lowercase_str(), Cow::Borrowed("a")));
+        assert!(matches!(gc("A").to_uppercase_str(), Cow::Borrowed("A")));
+        assert!(matches!(gc("1").to_titlecase_str(), Cow::Borrowed("1")));
+        assert!(matches!(gc("\u{1C5}").to_titlecase_str(), Cow::Borrowed(_)));
+        assert_eq!(gc("A\u{308}").to_lowercase_str(), "a\u{308}");
+        assert!(matches!(gc("A\u{308}").to_lowercase_str(), Cow::Owned(_)));
+        assert_eq!(gc("e\u{301}").to_uppercase_str(), "E\u{301}");
+        assert_eq!(gc("\u{DF}").to_uppercase_str(), "SS");
+        assert_eq!(gc("\u{DF}").to_titlecase_str(), "Ss");
+        assert_eq!(gc("\u{1C6}").to_titlecase_str(), "\u{1C5}");
+        assert_eq!(gc("\u{130}").to_lowercase_str(), "i\u{307}");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_case_gc() {
+        assert_eq!(gc("A\u{308}").to_lowercase_gc().unwrap(), "a\u{308}");
+        assert_eq!(gc("a").to_uppercase_gc().unwrap(), "A");
+        assert_eq!(gc("\u{1C6}").to_titlecase_gc().unwrap(), "\u{1C5}");
+
+        // "SS" and "Ss" are two clusters.
+        assert_eq!(gc("\u{DF}").to_uppercase_gc(), None);
+        assert_eq!(gc("\u{DF}").to_titlecase_gc(), None);
+        assert_eq!(gc("\u{DF}").to_lowercase_gc().unwrap(), "\u{DF}");
+        assert_eq!(gc("\u{FB01}").to_uppercase_gc(), None);
+
+        // The combining dot stays attached to the "i".
+        assert_eq!(gc("\u{130}").to_lowercase_gc().unwrap(), "i\u{307}");
+
+        // Without context, capital sigma always maps to the non-final form.
+        assert_eq!(gc("\u{3A3}").to_lowercase_gc().unwrap(), "\u{3C3}");
+        assert_eq!(gc("\u{3C2}").to_uppercase_gc().unwrap(), "\u{3A3}");
+    }
+
+    #[test]
+    fn test_repeat() {
+        assert_eq!(gc("\u{2500}").repeat(3), "\u{2500}\u{2500}\u{2500}");
+        assert_eq!(gc("a").repeat(0), "");
+        assert_eq!(gc("e\u{301}").repeat(2), "e\u{301}e\u{301}");
+        assert_eq!(gc("e\u{301}").repeat(2).capacity(), 6);
+        assert_eq!(gc("\u{1F468}\u{200D}\u{1F469}").repeat(2),
+            "\u{1F468}\u{200D}\u{1F469}\u{1F468}\u{200D}\u{1F469}");
+    }
+
+    #[cfg(all(feature = "alloc", feature = "width"))]
+    #[test]
+    fn test_repeat_to_width() {
+        assert_eq!(gc("-").repeat_to_width(3), "---");
+        assert_eq!(gc("-").repeat_to_width(0), "");
+        assert_eq!(gc("\u{6F22}").repeat_to_width(5), "\u{6F22}\u{6F22}");
+        assert_eq!(gc("\u{6F22}").repeat_to_width(1), "");
+        assert_eq!(gc("\u{1F7E6}").repeat_to_width(4), "\u{1F7E6}\u{1F7E6}");
+        assert_eq!(gc("e\u{301}").repeat_to_width(2), "e\u{301}e\u{301}");
+        assert_eq!(gc("\u{200D}").repeat_to_width(10), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_generic_bounds() {
+        use std::borrow::Borrow;
+        use std::collections::HashSet;
+
+        fn base_chars<T: AsRef<Gc>>(gcs: &[T]) -> String {
+            gcs.iter().map(|gc| gc.as_ref().base_char()).collect()
+        }
+        assert_eq!(base_chars(&[gc("a\u{308}"), gc("b")]), "ab");
+        assert_eq!(base_chars(&[GcBuf::from('x')]), "x");
+
+        fn len_of<T: Borrow<str> + ?Sized>(s: &T) -> usize {
+            s.borrow().len()
+        }
+        assert_eq!(len_of(gc("e\u{301}")), 3);
+
+        // Hashing is consistent with `str`.
+        let set: HashSet<&str> = vec!["a", "e\u{301}"].into_iter().collect();
+        assert!(set.contains(Borrow::<str>::borrow(gc("e\u{301}"))));
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(gc("7").to_digit(10), Some(7));
+        assert_eq!(gc("f").to_digit(16), Some(15));
+        assert_eq!(gc("f").to_digit(10), None);
+        assert_eq!(gc("\u{667}").to_digit(10), None);
+        assert_eq!(gc("7\u{FE0F}\u{20E3}").to_digit(10), None);
+
+        assert_eq!(gc("7").decimal_digit_value(), Some(7));
+        assert_eq!(gc("0").decimal_digit_value(), Some(0));
+        assert_eq!(gc("\u{667}").decimal_digit_value(), Some(7));
+        assert_eq!(gc("\u{96D}").decimal_digit_value(), Some(7));
+        assert_eq!(gc("\u{FF17}").decimal_digit_value(), Some(7));
+        assert_eq!(gc("\u{1D7FF}").decimal_digit_value(), Some(9));
+        assert_eq!(gc("7\u{FE0F}\u{20E3}").decimal_digit_value(), None);
+        assert_eq!(gc("\u{B2}").decimal_digit_value(), None);
+        assert_eq!(gc("a").decimal_digit_value(), None);
+        assert_eq!(gc("\u{6FF}").decimal_digit_value(), None);
+        assert_eq!(gc("\u{1}").decimal_digit_value(), None);
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_decimal_digit_table() {
+        use GeneralCategory;
+        for c in (0..0x110000).filter_map(::std::char::from_u32) {
+            assert_eq!(super::decimal_digit_value(c).is_some(), GeneralCategory::of(c) == GeneralCategory::Nd,
+                "{:?}", c);
+        }
+    }
+
+    #[cfg(feature = "unicode-properties")]
+    #[test]
+    fn test_extended_pictographic_table() {
+        use uniseg::UnicodeSegmentation;
+        // By rule GB11, a zero width joiner only binds a following code point to a preceding pictograph if that code point is also Extended_Pictographic.  Anything that also binds after a letter is Extend, ZWJ or SpacingMark instead.
+        let joins = |base: char, c: char| {
+            let s: String = vec![base, '\u{200D}', c].into_iter().collect();
+            s.graphemes(true).count() == 1
+        };
+        for c in (0..0x110000).filter_map(::std::char::from_u32) {
+            assert_eq!(::props::is_extended_pictographic(c), joins('\u{1F600}', c) && !joins('a', c),
+                "{:?}", c);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_from_chars() {
+        use GcParseError;
+        assert_eq!(GcBuf::from_chars(vec!['e', '\u{308}']).unwrap(), "e\u{308}");
+        assert_eq!(GcBuf::from_chars("a".chars()).unwrap(), "a");
+        assert_eq!(GcBuf::from_chars("\r\n".chars()).unwrap(), "\r\n");
+        assert_eq!(GcBuf::from_chars(gc("\u{1F468}\u{200D}\u{1F469}").chars()).unwrap(),
+            "\u{1F468}\u{200D}\u{1F469}");
+        assert_eq!(GcBuf::from_chars(None), Err(GcParseError::Empty));
+        assert_eq!(GcBuf::from_chars(vec!['a', 'b']), Err(GcParseError::MultipleClusters { first_cluster_len: 1 }));
+        assert_eq!(GcBuf::from_chars(vec!['\n', '\r']), Err(GcParseError::MultipleClusters { first_cluster_len: 1 }));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_collect_into_string() {
+        use std::borrow::Cow;
+        use StrCursor;
+        let s = "I \u{2764}\u{FE0F} cafe\u{301}s \u{1F468}\u{200D}\u{1F469}!";
+        let stripped: String = StrCursor::new_at_start(s).iter_after()
+            .filter(|gc| !gc.has_zwj() && !gc.has_variation_selector())
+            .collect();
+        assert_eq!(stripped, "I  cafe\u{301}s !");
+
+        let upper: String = StrCursor::new_at_start("noe\u{308}l").iter_after()
+            .map(|gc| gc.to_uppercase_gc().unwrap())
+            .collect();
+        assert_eq!(upper, "NOE\u{308}L");
+
+        let cow: Cow<str> = StrCursor::new_at_start(s).iter_after().take(3).collect();
+        assert_eq!(cow, "I \u{2764}\u{FE0F}");
+
+        let mut out = String::from(">");
+        for word in &["ab", "e\u{301}"] {
+            out.extend(StrCursor::new_at_start(word).iter_after());
+            out.extend(Some(GcBuf::from('|')));
+        }
+        assert_eq!(out, ">ab|e\u{301}|");
+
+        let emoji = "\u{1F1E6}\u{1F1FA}\u{1F44D}\u{1F3FD}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{2764}\u{FE0F}x";
+        let round: String = StrCursor::new_at_start(emoji).iter_after().collect();
+        assert_eq!(round, emoji);
+        let owned: String = StrCursor::new_at_start(emoji).iter_after().map(Gc::to_owned).collect();
+        assert_eq!(owned, emoji);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_hash_consistency() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashMap;
+        use std::hash::{Hash, Hasher};
+
+        fn hash<T: Hash + ?Sized>(v: &T) -> u64 {
+            let mut h = DefaultHasher::new();
+            v.hash(&mut h);
+            h.finish()
+        }
+        for s in &["a", "\u{E9}", "e\u{301}", "\r\n", "\u{1F468}\u{200D}\u{1F469}"] {
+            assert_eq!(hash(gc(s)), hash(*s));
+            assert_eq!(hash(&GcBuf::from(gc(s))), hash(*s));
+        }
+
+        let mut counts: HashMap<GcBuf, u64> = HashMap::new();
+        *counts.entry(GcBuf::from('\u{E9}')).or_insert(0) += 2;
+        *counts.entry(GcBuf::from(gc("e\u{301}"))).or_insert(0) += 1;
+        assert_eq!(counts.get::<str>("\u{E9}"), Some(&2));
+        assert_eq!(counts.get::<Gc>(gc("\u{E9}")), Some(&2));
+        assert_eq!(counts.get::<Gc>(gc("e\u{301}")), Some(&1));
+        assert_eq!(counts.get::<str>("e"), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_compose() {
+        use ComposeError;
+        assert_eq!(GcBuf::compose('e', "\u{301}").unwrap(), "e\u{301}");
+        assert_eq!(GcBuf::compose('e', "").unwrap(), "e");
+        assert_eq!(GcBuf::compose('a', "\u{20DD}").unwrap(), "a\u{20DD}");
+        assert_eq!(GcBuf::compose('e', "x"), Err(ComposeError::Break { byte_pos: 1, char_index: 1 }));
+        assert_eq!(GcBuf::compose('e', "\u{301}x\u{301}"),
+            Err(ComposeError::Break { byte_pos: 3, char_index: 2 }));
+        assert_eq!(GcBuf::compose('\n', "\u{301}"), Err(ComposeError::Break { byte_pos: 1, char_index: 1 }));
+
+        let mut chars = vec![];
+        for &c in &['\u{1F468}', '\u{200D}', '\u{1F469}', '\u{200D}', '\u{1F467}'] {
+            chars.push(c);
+            assert_eq!(GcBuf::try_from_chars(chars.iter().cloned()).unwrap().chars().count(), chars.len());
+        }
+        assert_eq!(GcBuf::try_from_chars(None), Err(ComposeError::Empty));
+        assert_eq!(GcBuf::try_from_chars(vec!['\u{E9}', 'x']),
+            Err(ComposeError::Break { byte_pos: 2, char_index: 1 }));
+        assert_eq!(GcBuf::try_from_chars(vec!['\u{E9}', 'x']).unwrap_err().to_string(),
+            "grapheme cluster breaks before code point 1 (byte offset 2)");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_parse() {
+        use std::convert::TryFrom;
+        use GcParseError;
+        assert_eq!("".parse::<GcBuf>(), Err(GcParseError::Empty));
+        assert_eq!("a".parse::<GcBuf>().unwrap(), "a");
+        assert_eq!("a\u{308}".parse::<GcBuf>().unwrap(), "a\u{308}");
+        assert_eq!("ab".parse::<GcBuf>(), Err(GcParseError::MultipleClusters { first_cluster_len: 1 }));
+        assert_eq!("a\u{308}b".parse::<GcBuf>(),
+            Err(GcParseError::MultipleClusters { first_cluster_len: 3 }));
+
+        assert_eq!(GcBuf::try_from("a\u{308}").unwrap(), "a\u{308}");
+        assert_eq!(GcBuf::try_from(""), Err(GcParseError::Empty));
+
+        assert_eq!(GcBuf::try_from(String::from("\r\n")).unwrap(), "\r\n");
+        let err = GcBuf::try_from(String::from("ab")).unwrap_err();
+        assert_eq!(err.error(), GcParseError::MultipleClusters { first_cluster_len: 1 });
+        assert_eq!(err.as_str(), "ab");
+        assert_eq!(err.to_string(), "string contains more than one grapheme cluster");
+        assert_eq!(err.into_string(), "ab");
+        assert_eq!(GcBuf::try_from(String::new()).unwrap_err().error(), GcParseError::Empty);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_base() {
+        use GcParseError;
+        let mut g = GcBuf::from(gc("e\u{308}"));
+        assert_eq!(g.set_base('a'), Ok(()));
+        assert_eq!(g, "a\u{308}");
+        assert_eq!(g.set_base('\u{1F4AA}'), Ok(()));
+        assert_eq!(g, "\u{1F4AA}\u{308}");
+
+        let mut g = GcBuf::from('x');
+        assert_eq!(g.set_base('\u{E9}'), Ok(()));
+        assert_eq!(g, "\u{E9}");
+
+        // A control character cannot take marks.
+        let mut g = GcBuf::from(gc("e\u{308}"));
+        assert_eq!(g.set_base('\n'), Err(GcParseError::MultipleClusters { first_cluster_len: 1 }));
+        assert_eq!(g, "e\u{308}");
+
+        let mut g = GcBuf::from(gc("\u{1F1E6}\u{1F1FA}"));
+        assert_eq!(g.set_base('a'), Err(GcParseError::MultipleClusters { first_cluster_len: 1 }));
+        assert_eq!(g.set_base('\u{1F1F3}'), Ok(()));
+        assert_eq!(g, "\u{1F1F3}\u{1F1FA}");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_make_ascii_case() {
+        let mut g = GcBuf::from(gc("e\u{301}\u{323}"));
+        let ptr = g.as_str().as_ptr();
+        g.make_ascii_uppercase();
+        assert_eq!(g, "E\u{301}\u{323}");
+        assert_eq!(g.as_str().as_ptr(), ptr);
+        g.make_ascii_lowercase();
+        assert_eq!(g, "e\u{301}\u{323}");
+
+        // Non-ASCII bases are left alone.
+        let mut g = GcBuf::from(gc("\u{E9}\u{323}"));
+        g.make_ascii_uppercase();
+        assert_eq!(g, "\u{E9}\u{323}");
+        let mut g = GcBuf::from('\u{C9}');
+        g.make_ascii_lowercase();
+        assert_eq!(g, "\u{C9}");
+
+        let mut g = GcBuf::from('q');
+        g.make_ascii_uppercase();
+        assert_eq!(g, "Q");
+        let mut g = GcBuf::from(gc("\r\n"));
+        g.make_ascii_uppercase();
+        assert_eq!(g, "\r\n");
+    }
+
+    #[cfg(all(feature = "alloc", feature = "unicode-properties"))]
+    #[test]
+    fn test_pop_mark() {
+        let mut g = GcBuf::from(gc("a\u{301}\u{323}"));
+        assert_eq!(g.pop_mark(), Some('\u{323}'));
+        assert_eq!(g, "a\u{301}");
+        assert_eq!(g.pop_mark(), Some('\u{301}'));
+        assert_eq!(g, "a");
+        assert_eq!(g.pop_mark(), None);
+        assert_eq!(g, "a");
+
+        let mut g = GcBuf::from(gc("\u{1F468}\u{200D}\u{1F469}"));
+        assert_eq!(g.pop_mark(), None);
+        assert_eq!(g, "\u{1F468}\u{200D}\u{1F469}");
+        let mut g = GcBuf::from(gc("\u{1F44D}\u{1F3FD}"));
+        assert_eq!(g.pop_mark(), None);
+        assert_eq!(g, "\u{1F44D}\u{1F3FD}");
+        let mut g = GcBuf::from(gc("\u{1F1E6}\u{1F1FA}"));
+        assert_eq!(g.pop_mark(), None);
+        assert_eq!(g, "\u{1F1E6}\u{1F1FA}");
+        let mut g = GcBuf::from(gc("\u{1100}\u{1161}"));
+        assert_eq!(g.pop_mark(), None);
+        assert_eq!(g, "\u{1100}\u{1161}");
+
+        let mut g = GcBuf::from(gc("\u{1F44D}\u{1F3FD}\u{20E3}"));
+        assert_eq!(g.pop_mark(), Some('\u{20E3}'));
+        assert_eq!(g, "\u{1F44D}\u{1F3FD}");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_eq_ignore_case() {
+        assert!(gc("a").eq_ignore_case(gc("A")));
+        assert!(!gc("a").eq_ignore_case(gc("b")));
+        assert!(gc("\u{DF}").eq_ignore_case(gc("\u{1E9E}")));
+        assert!(gc("\u{130}").eq_ignore_case(gc("i\u{307}")));
+        assert!(!gc("\u{130}").eq_ignore_case(gc("i")));
+        assert!(gc("\u{3C2}").eq_ignore_case(gc("\u{3A3}")));
+        assert!(gc("A\u{308}").eq_ignore_case(gc("a\u{308}")));
+        assert!(!gc("A\u{308}").eq_ignore_case(gc("\u{E4}")));
+        assert!(!gc("a\u{308}").eq_ignore_case(gc("a")));
+        assert!(gc("\u{C4}").eq_ignore_case(gc("\u{E4}")));
+        let b = GcBuf::from(gc("\u{C4}"));
+        assert!(b.eq_ignore_case(&GcBuf::from(gc("\u{E4}"))));
+        assert!(b.eq_ignore_case(gc("\u{E4}")));
+    }
+
+    #[test]
+    fn test_eq_str_ignore_case() {
+        assert!(gc("a").eq_str_ignore_case("A"));
+        assert!(gc("I").eq_str_ignore_case("i"));
+        assert!(!gc("i").eq_str_ignore_case("if"));
+        assert!(!gc("a").eq_str_ignore_case(""));
+        assert!(gc("\r\n").eq_str_ignore_case("\r\n"));
+        assert!(gc("\u{DF}").eq_str_ignore_case("\u{1E9E}"));
+        assert!(gc("\u{DF}").eq_str_ignore_case("SS"));
+        assert!(gc("\u{DF}").eq_str_ignore_case("ss"));
+        assert!(!gc("\u{DF}").eq_str_ignore_case("s"));
+        assert!(gc("\u{130}").eq_str_ignore_case("i\u{307}"));
+        assert!(!gc("\u{130}").eq_str_ignore_case("i"));
+        assert!(gc("\u{C4}").eq_str_ignore_case("\u{E4}"));
+        assert!(!gc("A\u{308}").eq_str_ignore_case("\u{E4}"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_gc_inline() {
+        use super::GcInline;
+        for &c in &['a', '\u{E9}', '\u{20AC}', '\u{1F4AA}', '\u{301}'] {
+            let g = GcInline::from_char(c);
+            assert_eq!(g.as_gc(), GcBuf::from(c).as_gc());
+            assert_eq!(g.len(), c.len_utf8());
+            assert_eq!(g.try_as_char(), Some(c));
+            assert_eq!(g.base_char(), c);
+        }
+        let g = GcInline::from('x');
+        assert_eq!(*g, *gc("x"));
+        assert_eq!(format!("{} {:?}", g, g), "x \"x\"");
+        assert!(g.is_ascii());
+        assert_eq!(GcInline::from('a'), GcInline::from_char('a'));
+        assert!(GcInline::from('a') < GcInline::from('b'));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_leak() {
+        let g: &'static Gc = GcBuf::from(gc("a\u{308}")).leak();
+        assert_eq!(g, "a\u{308}");
+        assert_eq!(g.base_char(), 'a');
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_shared_conversions() {
+        let g = gc("a\u{308}");
+        let b: Box<str> = g.into();
+        assert_eq!(&*b, "a\u{308}");
+        let a: Arc<str> = GcBuf::from(g).into();
+        assert_eq!(&*a, "a\u{308}");
+        let r: Rc<str> = GcBuf::from(g).into();
+        assert_eq!(&*r, "a\u{308}");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_shared_gc_conversions() {
+        use std::borrow::Cow;
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash<T: Hash + ?Sized>(v: &T) -> u64 {
+            let mut h = DefaultHasher::new();
+            v.hash(&mut h);
+            h.finish()
+        }
+
+        let g = gc("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}");
+        let b: Box<Gc> = GcBuf::from(g).into();
+        assert_eq!(&*b, g);
+        assert_eq!(b.as_bytes(), g.as_bytes());
+        let back: GcBuf = b.into();
+        assert_eq!(back, g);
+        assert_eq!(back.as_bytes(), g.as_bytes());
+
+        let r: Rc<Gc> = g.into();
+        assert_eq!(&*r, g);
+        assert_eq!(*r, *gc("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"));
+        assert!(*r != *gc("\u{1F468}"));
+        assert_eq!(hash(&r), hash(g));
+        assert_eq!(r.base_char(), '\u{1F468}');
+        let r2 = r.clone();
+        assert_eq!(r, r2);
+        assert_eq!(Rc::strong_count(&r), 2);
+
+        let a: Arc<Gc> = gc("e\u{301}").into();
+        assert_eq!(&*a, "e\u{301}");
+        assert_eq!(hash(&a), hash(gc("e\u{301}")));
+
+        let c: Cow<Gc> = g.into();
+        assert!(matches!(c, Cow::Borrowed(_)));
+        assert_eq!(c, g);
+        let c: Cow<Gc> = GcBuf::from('x').into();
+        assert!(matches!(c, Cow::Owned(_)));
+        assert_eq!(c, *gc("x"));
+    }
+
     #[test]
     fn test_mark_str() {
         assert_eq!(gc("a").mark_str(), "");
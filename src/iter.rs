@@ -0,0 +1,710 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Iterators produced by cursors.
+*/
+#[cfg(feature = "normalization")] use alloc::borrow::Cow;
+#[cfg(feature = "std")] use alloc::borrow::ToOwned;
+#[cfg(feature = "std")] use alloc::vec::Vec;
+#[cfg(feature = "std")] use std::collections::HashMap;
+use grapheme::Gc;
+#[cfg(feature = "std")] use grapheme::GcBuf;
+use uniseg::UnicodeSegmentation as UniSeg;
+use StrCursor;
+
+/**
+An iterator over the grapheme clusters after a cursor, returned from [`StrCursor::iter_after`](struct.StrCursor.html#method.iter_after).
+*/
+#[derive(Clone, Debug)]
+pub struct IterAfter<'a> {
+    cur: StrCursor<'a>,
+    end: StrCursor<'a>,
+}
+
+impl<'a> IterAfter<'a> {
+    /**
+    Returns a cursor positioned after the last grapheme cluster yielded by this iterator.
+    */
+    pub fn cursor(&self) -> StrCursor<'a> {
+        self.cur
+    }
+
+    /**
+    Adapts this iterator to also yield a cursor positioned immediately before each grapheme cluster.
+    */
+    pub fn with_cursor(self) -> WithCursor<IterAfter<'a>> {
+        WithCursor { iter: self }
+    }
+
+    /**
+    Returns the number of grapheme clusters this iterator has yet to yield.
+
+    This takes time proportional to the length of the remaining text, but does not advance the iterator.  It is equivalent to `self.clone().count()`.
+    */
+    pub fn remaining_count(&self) -> usize {
+        UniSeg::graphemes(&self.cur.s[self.cur.at..self.end.at], /*is_extended:*/true).count()
+    }
+
+    /**
+    Adapts this iterator to yield each grapheme cluster in Normalization Form C.
+
+    See [`Normalized`](struct.Normalized.html) for details.
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn normalized(self) -> Normalized<IterAfter<'a>> {
+        Normalized { iter: self }
+    }
+}
+
+impl<'a> Iterator for IterAfter<'a> {
+    type Item = &'a Gc;
+
+    fn next(&mut self) -> Option<&'a Gc> {
+        if self.cur.at >= self.end.at {
+            return None;
+        }
+        let (gc, cur) = self.cur.next()?;
+        self.cur = cur;
+        Some(gc)
+    }
+}
+
+impl<'a> DoubleEndedIterator for IterAfter<'a> {
+    fn next_back(&mut self) -> Option<&'a Gc> {
+        if self.cur.at >= self.end.at {
+            return None;
+        }
+        let (gc, end) = self.end.prev()?;
+        self.end = end;
+        Some(gc)
+    }
+}
+
+/**
+An iterator over the grapheme clusters between two cursors, returned from [`StrCursor::iter_between`](struct.StrCursor.html#method.iter_between).
+*/
+#[derive(Clone, Debug)]
+pub struct IterBetween<'a> {
+    cur: StrCursor<'a>,
+    end: StrCursor<'a>,
+}
+
+impl<'a> IterBetween<'a> {
+    /**
+    Returns a cursor positioned after the last grapheme cluster yielded by this iterator.
+    */
+    pub fn cursor(&self) -> StrCursor<'a> {
+        self.cur
+    }
+
+    /**
+    Adapts this iterator to also yield a cursor positioned immediately before each grapheme cluster.
+    */
+    pub fn with_cursor(self) -> WithCursor<IterBetween<'a>> {
+        WithCursor { iter: self }
+    }
+}
+
+impl<'a> Iterator for IterBetween<'a> {
+    type Item = &'a Gc;
+
+    fn next(&mut self) -> Option<&'a Gc> {
+        if self.cur.at >= self.end.at {
+            return None;
+        }
+        let (gc, cur) = self.cur.next()?;
+        if cur.at > self.end.at {
+            // The end cursor is in the middle of this cluster.
+            self.end = self.cur;
+            return None;
+        }
+        self.cur = cur;
+        Some(gc)
+    }
+}
+
+/**
+An iterator adapter which yields each grapheme cluster along with a cursor positioned immediately before it, returned from [`IterAfter::with_cursor`](struct.IterAfter.html#method.with_cursor) and [`IterBetween::with_cursor`](struct.IterBetween.html#method.with_cursor).
+*/
+#[derive(Clone, Debug)]
+pub struct WithCursor<I> {
+    iter: I,
+}
+
+impl<'a> Iterator for WithCursor<IterAfter<'a>> {
+    type Item = (StrCursor<'a>, &'a Gc);
+
+    fn next(&mut self) -> Option<(StrCursor<'a>, &'a Gc)> {
+        let cur = self.iter.cursor();
+        self.iter.next().map(|gc| (cur, gc))
+    }
+}
+
+impl<'a> Iterator for WithCursor<IterBetween<'a>> {
+    type Item = (StrCursor<'a>, &'a Gc);
+
+    fn next(&mut self) -> Option<(StrCursor<'a>, &'a Gc)> {
+        let cur = self.iter.cursor();
+        self.iter.next().map(|gc| (cur, gc))
+    }
+}
+
+/**
+An iterator over the grapheme clusters before a cursor, in reverse order, returned from [`StrCursor::iter_before`](struct.StrCursor.html#method.iter_before).
+*/
+#[derive(Clone, Debug)]
+pub struct IterBefore<'a> {
+    cur: StrCursor<'a>,
+    start: StrCursor<'a>,
+}
+
+impl<'a> IterBefore<'a> {
+    /**
+    Returns a cursor positioned before the last grapheme cluster yielded by this iterator.
+    */
+    pub fn cursor(&self) -> StrCursor<'a> {
+        self.cur
+    }
+
+    /**
+    Returns the number of grapheme clusters this iterator has yet to yield.
+
+    This takes time proportional to the length of the remaining text, but does not advance the iterator.  It is equivalent to `self.clone().count()`.
+    */
+    pub fn remaining_count(&self) -> usize {
+        UniSeg::graphemes(&self.cur.s[self.start.at..self.cur.at], /*is_extended:*/true).count()
+    }
+
+    /**
+    Adapts this iterator to yield each grapheme cluster in Normalization Form C.
+
+    See [`Normalized`](struct.Normalized.html) for details.
+
+    Requires the `normalization` feature.
+    */
+    #[cfg(feature = "normalization")]
+    pub fn normalized(self) -> Normalized<IterBefore<'a>> {
+        Normalized { iter: self }
+    }
+}
+
+impl<'a> Iterator for IterBefore<'a> {
+    type Item = &'a Gc;
+
+    fn next(&mut self) -> Option<&'a Gc> {
+        if self.cur.at <= self.start.at {
+            return None;
+        }
+        let (gc, cur) = self.cur.prev()?;
+        self.cur = cur;
+        Some(gc)
+    }
+}
+
+impl<'a> DoubleEndedIterator for IterBefore<'a> {
+    fn next_back(&mut self) -> Option<&'a Gc> {
+        if self.cur.at <= self.start.at {
+            return None;
+        }
+        let (gc, start) = self.start.next()?;
+        self.start = start;
+        Some(gc)
+    }
+}
+
+/**
+An iterator adapter which yields grapheme clusters in Normalization Form C, returned from [`IterAfter::normalized`](struct.IterAfter.html#method.normalized) and [`IterBefore::normalized`](struct.IterBefore.html#method.normalized).
+
+Clusters which are already in NFC are yielded as borrowed slices of the original string; only clusters which change under normalization are allocated.
+
+Requires the `normalization` feature.
+*/
+#[cfg(feature = "normalization")]
+#[derive(Clone, Debug)]
+pub struct Normalized<I> {
+    iter: I,
+}
+
+#[cfg(feature = "normalization")]
+impl<'a, I> Iterator for Normalized<I> where I: Iterator<Item=&'a Gc> {
+    type Item = Cow<'a, Gc>;
+
+    fn next(&mut self) -> Option<Cow<'a, Gc>> {
+        use unicode_normalization::is_nfc;
+        let gc = self.iter.next()?;
+        if is_nfc(gc.as_str()) {
+            Some(Cow::Borrowed(gc))
+        } else {
+            Some(Cow::Owned(gc.nfc()))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/**
+An iterator over overlapping windows of grapheme clusters, returned from [`StrCursor::windows`](struct.StrCursor.html#method.windows).
+*/
+#[derive(Clone, Debug)]
+pub struct GraphemeWindows<'a> {
+    start: StrCursor<'a>,
+    end: Option<StrCursor<'a>>,
+}
+
+impl<'a> Iterator for GraphemeWindows<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let end = self.end?;
+        let window = self.start.slice_between(end);
+        self.end = end.at_next();
+        if self.end.is_some() {
+            self.start.seek_next();
+        }
+        window
+    }
+}
+
+/**
+An iterator over non-overlapping chunks of grapheme clusters, returned from [`StrCursor::chunks`](struct.StrCursor.html#method.chunks).
+*/
+#[derive(Clone, Debug)]
+pub struct GraphemeChunks<'a> {
+    cur: StrCursor<'a>,
+    size: usize,
+}
+
+impl<'a> Iterator for GraphemeChunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let start = self.cur;
+        let mut taken = 0;
+        while taken < self.size {
+            match self.cur.at_next() {
+                Some(cur) => self.cur = cur,
+                None => break
+            }
+            taken += 1;
+        }
+        if taken == 0 {
+            None
+        } else {
+            start.slice_between(self.cur)
+        }
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns an iterator over the grapheme clusters to the right of the cursor.
+
+    The iterator is double-ended; calling `rev` on it yields the same clusters as `iter_before` on a cursor at the end of the string, stopping at this cursor.
+    */
+    pub fn iter_after(self) -> IterAfter<'a> {
+        IterAfter { cur: self, end: StrCursor::new_at_end(self.s) }
+    }
+
+    /**
+    Returns an iterator over the grapheme clusters between this cursor and `end`.
+
+    Iteration stops at `end`.  The iterator is empty if `end` is to the left of this cursor, or if the cursors are from different strings (even different subsets of the same string).
+    */
+    pub fn iter_between(self, end: StrCursor<'a>) -> IterBetween<'a> {
+        let end = if ::str_eq_literal(self.s, end.s) && self.at <= end.at { end } else { self };
+        IterBetween { cur: self, end }
+    }
+
+    /**
+    Returns an iterator over the code points between this cursor and `end`.
+
+    The iterator is empty if `end` is to the left of this cursor, or if the cursors are from different strings (even different subsets of the same string).
+
+    # Note
+
+    Where possible, you should prefer `iter_between`.
+    */
+    pub fn iter_cp_between(self, end: StrCursor<'a>) -> ::core::str::Chars<'a> {
+        if ::str_eq_literal(self.s, end.s) && self.at <= end.at {
+            self.s[self.byte_pos()..end.byte_pos()].chars()
+        } else {
+            "".chars()
+        }
+    }
+
+    /**
+    Returns an iterator over the grapheme clusters to the left of the cursor, moving *leftward*; that is, the cluster immediately before the cursor is yielded first.
+    */
+    pub fn iter_before(self) -> IterBefore<'a> {
+        IterBefore { cur: self, start: StrCursor::new_at_start(self.s) }
+    }
+
+    /**
+    Returns iterators over the grapheme clusters to the left and right of the cursor.
+
+    This is equivalent to `(self.iter_before(), self.iter_after())`, and is intended for walking outward from a single anchor position.  The first iterator moves leftward, the second rightward; the two cover disjoint regions of the string, so no cluster is yielded by both.
+    */
+    pub fn iter_both(self) -> (IterBefore<'a>, IterAfter<'a>) {
+        (self.iter_before(), self.iter_after())
+    }
+
+    /**
+    Returns the number of grapheme clusters to the right of the cursor for which `pred` returns `true`.
+
+    This is equivalent to `self.iter_after().filter(pred).count()`.
+    */
+    pub fn count_after<P>(self, mut pred: P) -> usize
+    where P: FnMut(&Gc) -> bool {
+        UniSeg::graphemes(self.slice_after(), /*is_extended:*/true)
+            .filter(|gr| pred(unsafe { Gc::from_str_unchecked(gr) }))
+            .count()
+    }
+
+    /**
+    Returns the number of grapheme clusters to the left of the cursor for which `pred` returns `true`.
+
+    Clusters are tested moving *leftward*, as with `iter_before`.  This is equivalent to `self.iter_before().filter(pred).count()`.
+    */
+    pub fn count_before<P>(self, mut pred: P) -> usize
+    where P: FnMut(&Gc) -> bool {
+        UniSeg::graphemes(self.slice_before(), /*is_extended:*/true).rev()
+            .filter(|gr| pred(unsafe { Gc::from_str_unchecked(gr) }))
+            .count()
+    }
+
+    /**
+    Returns the number of times each distinct grapheme cluster occurs to the right of the cursor.
+
+    Clusters are compared exactly, without normalization: `"\u{E9}"` and `"e\u{301}"` are counted separately.
+
+    Requires the `std` feature.
+    */
+    #[cfg(feature = "std")]
+    pub fn tally_after(self) -> HashMap<GcBuf, usize> {
+        let mut map = HashMap::new();
+        self.tally_after_into(&mut map);
+        map
+    }
+
+    /**
+    Adds the number of times each distinct grapheme cluster occurs to the right of the cursor to the counts already in `map`.
+
+    This allows a single map to be reused across several strings.  Only clusters not already present in `map` are allocated.
+
+    Requires the `std` feature.
+    */
+    #[cfg(feature = "std")]
+    pub fn tally_after_into(self, map: &mut HashMap<GcBuf, usize>) {
+        for gr in UniSeg::graphemes(self.slice_after(), /*is_extended:*/true) {
+            let gc = unsafe { Gc::from_str_unchecked(gr) };
+            if let Some(count) = map.get_mut(gc) {
+                *count += 1;
+                continue;
+            }
+            map.insert(gc.to_owned(), 1);
+        }
+    }
+
+    /**
+    Returns the `n` most common grapheme clusters to the right of the cursor, along with their counts.
+
+    The result is ordered from most to least common; clusters with equal counts are ordered by their contents, so the result is deterministic.  Fewer than `n` entries are returned if there are fewer than `n` distinct clusters.
+
+    Requires the `std` feature.
+    */
+    #[cfg(feature = "std")]
+    pub fn most_common_after(self, n: usize) -> Vec<(GcBuf, usize)> {
+        let mut counts: Vec<_> = self.tally_after().into_iter().collect();
+        counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(n);
+        counts
+    }
+
+    /**
+    Returns an iterator over every window of `n` consecutive grapheme clusters after the cursor, as string slices.
+
+    Each window advances by one grapheme cluster.  If there are fewer than `n` clusters after the cursor, the iterator is empty.  This is the grapheme cluster analogue of `slice::windows`.
+
+    # Panics
+
+    If `n` is zero, then this function will panic.
+    */
+    pub fn windows(self, n: usize) -> GraphemeWindows<'a> {
+        if n == 0 {
+            panic!("window size must be non-zero");
+        }
+        let mut end = Some(self);
+        for _ in 0..n {
+            end = end.and_then(StrCursor::at_next);
+        }
+        GraphemeWindows {
+            start: self,
+            end,
+        }
+    }
+
+    /**
+    Returns an iterator over successive chunks of `n` grapheme clusters after the cursor, as string slices.
+
+    The chunks do not overlap.  The last chunk will contain fewer than `n` clusters if there are not enough remaining.  This is the grapheme cluster analogue of `slice::chunks`.
+
+    # Panics
+
+    If `n` is zero, then this function will panic.
+    */
+    pub fn chunks(self, n: usize) -> GraphemeChunks<'a> {
+        if n == 0 {
+            panic!("chunk size must be non-zero");
+        }
+        GraphemeChunks {
+            cur: self,
+            size: n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+    use std::string::String;
+    use std::vec::Vec;
+    use StrCursor;
+
+    #[test]
+    fn test_iter_after_before() {
+        let s = "noe\u{308}l 💪";
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 2);
+        assert_eq!(cur.iter_after().map(|gc| gc.as_str()).collect::<Vec<_>>(),
+            vec!["e\u{308}", "l", " ", "💪"]);
+        assert_eq!(cur.iter_before().map(|gc| gc.as_str()).collect::<Vec<_>>(),
+            vec!["o", "n"]);
+        let mut it = cur.iter_after();
+        it.next();
+        assert_eq!(it.cursor().byte_pos(), 5);
+        let mut it = cur.iter_before();
+        it.next();
+        assert_eq!(it.cursor().byte_pos(), 1);
+        assert_eq!(StrCursor::new_at_end(s).iter_after().next(), None);
+        assert_eq!(StrCursor::new_at_start(s).iter_before().next(), None);
+    }
+
+    #[test]
+    fn test_iter_rev() {
+        // Family ZWJ sequence, flag, skin-toned thumb, combining marks, and a CRLF.
+        let s = "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{1F1E6}\u{1F1FA}\r\n\u{1F44D}\u{1F3FD}e\u{301}\u{20DD}!";
+        let fwd_rev: Vec<_> = StrCursor::new_at_start(s).iter_after().rev().collect();
+        let back: Vec<_> = StrCursor::new_at_end(s).iter_before().collect();
+        assert_eq!(back, fwd_rev);
+        assert_eq!(back.len(), 7);
+
+        let back_rev: Vec<_> = StrCursor::new_at_end(s).iter_before().rev().collect();
+        let fwd: Vec<_> = StrCursor::new_at_start(s).iter_after().collect();
+        assert_eq!(fwd, back_rev);
+
+        // Meeting in the middle never yields a cluster twice.
+        let mut it = StrCursor::new_at_start(s).iter_after();
+        assert_eq!(it.next().map(|gc| gc.as_str()), Some("a"));
+        assert_eq!(it.next_back().map(|gc| gc.as_str()), Some("!"));
+        assert_eq!(it.by_ref().count(), 5);
+        assert_eq!(it.next_back(), None);
+
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 1);
+        assert_eq!(cur.iter_before().rev().map(|gc| gc.as_str()).collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(cur.iter_after().rev().next_back(), cur.iter_after().next());
+    }
+
+    #[test]
+    fn test_iter_between() {
+        let s = "noe\u{308}l 💪!";
+        let a = StrCursor::new_at_left_of_byte_pos(s, 1);
+        let b = StrCursor::new_at_left_of_byte_pos(s, 7);
+        assert_eq!(a.iter_between(b).map(|gc| gc.as_str()).collect::<Vec<_>>(),
+            vec!["o", "e\u{308}", "l", " "]);
+        assert_eq!(a.iter_cp_between(b).collect::<String>(), "oe\u{308}l ");
+        assert_eq!(a.iter_between(a).next(), None);
+        assert_eq!(b.iter_between(a).next(), None);
+        assert_eq!(b.iter_cp_between(a).next(), None);
+        assert_eq!(a.iter_between(StrCursor::new_at_end(&s[1..])).next(), None);
+        assert_eq!(a.iter_cp_between(StrCursor::new_at_end(&s[1..])).next(), None);
+
+        // An end cursor inside a cluster stops iteration before that cluster.
+        let mid = StrCursor::new_at_cp_left_of_byte_pos(s, 3);
+        assert_eq!(a.iter_between(mid).map(|gc| gc.as_str()).collect::<Vec<_>>(), vec!["o"]);
+
+        let mut it = a.iter_between(b);
+        it.next();
+        assert_eq!(it.cursor().byte_pos(), 2);
+    }
+
+    #[test]
+    fn test_with_cursor() {
+        let s = "noe\u{308}l";
+        let cur = StrCursor::new_at_start(s);
+        assert_eq!(cur.iter_after().with_cursor()
+                .map(|(cur, gc)| (cur.byte_pos(), gc.as_str())).collect::<Vec<_>>(),
+            vec![(0, "n"), (1, "o"), (2, "e\u{308}"), (5, "l")]);
+        assert_eq!(cur.iter_between(StrCursor::new_at_left_of_byte_pos(s, 5)).with_cursor()
+                .map(|(cur, gc)| (cur.slice_after(), gc.as_str())).collect::<Vec<_>>(),
+            vec![("noe\u{308}l", "n"), ("oe\u{308}l", "o"), ("e\u{308}l", "e\u{308}")]);
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_normalized() {
+        use std::borrow::Cow;
+        let s = "noe\u{308}l \u{e9}\u{1E0B}\u{323}";
+        let cur = StrCursor::new_at_start(s);
+        let gcs: Vec<_> = cur.iter_after().normalized().collect();
+        assert_eq!(gcs.iter().map(|gc| gc.as_str()).collect::<Vec<_>>(),
+            vec!["n", "o", "\u{eb}", "l", " ", "\u{e9}", "\u{1E0D}\u{307}"]);
+        match gcs[0] {
+            Cow::Borrowed(gc) => assert_eq!(gc.as_str().as_ptr(), s.as_ptr()),
+            Cow::Owned(_) => panic!("expected borrowed cluster"),
+        }
+        assert!(matches!(gcs[2], Cow::Owned(_)));
+        assert!(matches!(gcs[5], Cow::Borrowed(_)));
+        assert_eq!(StrCursor::new_at_end(s).iter_before().normalized()
+                .map(|gc| gc.as_str().to_owned()).collect::<Vec<_>>(),
+            vec!["\u{1E0D}\u{307}", "\u{e9}", " ", "l", "\u{eb}", "o", "n"]);
+    }
+
+    #[test]
+    fn test_iter_both() {
+        let s = "noe\u{308}l 💪";
+        let (before, after) = StrCursor::new_at_left_of_byte_pos(s, 5).iter_both();
+        assert_eq!(before.map(|gc| gc.as_str()).collect::<Vec<_>>(), vec!["e\u{308}", "o", "n"]);
+        assert_eq!(after.map(|gc| gc.as_str()).collect::<Vec<_>>(), vec!["l", " ", "💪"]);
+
+        let (mut before, after) = StrCursor::new_at_start(s).iter_both();
+        assert_eq!(before.next(), None);
+        assert_eq!(after.count(), 6);
+    }
+
+    #[test]
+    fn test_count() {
+        let s = "a e\u{308}\t\u{1F4AA}\u{1F3FD} b\r\n";
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 2);
+        assert_eq!(cur.count_after(|gc| gc.is_whitespace()), 3);
+        assert_eq!(cur.count_before(|gc| gc.is_whitespace()), 1);
+        assert_eq!(cur.count_after(|gc| gc.has_marks()), 3);
+        assert_eq!(cur.count_after(|_| true), cur.iter_after().count());
+        assert_eq!(StrCursor::new_at_end(s).count_after(|_| true), 0);
+
+        let mut seen = vec![];
+        StrCursor::new_at_end(s).count_before(|gc| { seen.push(gc.as_str().to_owned()); false });
+        assert_eq!(seen, StrCursor::new_at_end(s).iter_before().map(|gc| gc.as_str()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_windows() {
+        let s = "noe\u{308}l 💪";
+        let cur = StrCursor::new_at_start(s);
+        assert_eq!(cur.windows(1).collect::<Vec<_>>(),
+            vec!["n", "o", "e\u{308}", "l", " ", "💪"]);
+        assert_eq!(cur.windows(3).collect::<Vec<_>>(),
+            vec!["noe\u{308}", "oe\u{308}l", "e\u{308}l ", "l 💪"]);
+        assert_eq!(cur.windows(6).collect::<Vec<_>>(), vec![s]);
+        assert_eq!(cur.windows(7).next(), None);
+        assert_eq!(cur.at_next().unwrap().windows(5).collect::<Vec<_>>(),
+            vec!["oe\u{308}l 💪"]);
+        assert_eq!(StrCursor::new_at_end(s).windows(1).next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_windows_zero() {
+        StrCursor::new_at_start("abc").windows(0);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let s = "noe\u{308}l 💪";
+        let cur = StrCursor::new_at_start(s);
+        assert_eq!(cur.chunks(1).collect::<Vec<_>>(),
+            vec!["n", "o", "e\u{308}", "l", " ", "💪"]);
+        assert_eq!(cur.chunks(2).collect::<Vec<_>>(),
+            vec!["no", "e\u{308}l", " 💪"]);
+        assert_eq!(cur.chunks(4).collect::<Vec<_>>(),
+            vec!["noe\u{308}l", " 💪"]);
+        assert_eq!(cur.chunks(7).collect::<Vec<_>>(), vec![s]);
+        assert_eq!(cur.at_next().unwrap().chunks(3).collect::<Vec<_>>(),
+            vec!["oe\u{308}l", " 💪"]);
+        assert_eq!(StrCursor::new_at_end(s).chunks(1).next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_chunks_zero() {
+        StrCursor::new_at_start("abc").chunks(0);
+    }
+
+    #[test]
+    fn test_remaining_count() {
+        let s = "noe\u{308}l \u{1F1E6}\u{1F1FA}\u{1F1F3}\u{1F1FF}\r\n!";
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 2);
+
+        let mut it = cur.iter_after();
+        loop {
+            let n = it.remaining_count();
+            assert_eq!(n, it.clone().count());
+            if it.next().is_none() {
+                assert_eq!(n, 0);
+                break;
+            }
+        }
+
+        let mut it = cur.iter_after();
+        assert_eq!(it.remaining_count(), 7);
+        it.next_back();
+        it.next();
+        assert_eq!(it.remaining_count(), 5);
+
+        let mut it = StrCursor::new_at_end(s).iter_before();
+        let mut n = it.remaining_count();
+        assert_eq!(n, 9);
+        while it.next().is_some() {
+            n -= 1;
+            assert_eq!(it.remaining_count(), n);
+        }
+        assert_eq!(n, 0);
+        assert_eq!(cur.iter_before().remaining_count(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_tally_after() {
+        use std::collections::HashMap;
+        use grapheme::GcBuf;
+
+        let s = "x\u{E9}e\u{301}\u{E9}ee\u{301}\u{E9}";
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 1);
+        let map = cur.tally_after();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map[&GcBuf::from('\u{E9}')], 3);
+        assert_eq!(map["e\u{301}"], 2);
+        assert_eq!(map["e"], 1);
+        assert!(!map.contains_key("x"));
+
+        let mut map = HashMap::new();
+        cur.tally_after_into(&mut map);
+        StrCursor::new_at_start("ee").tally_after_into(&mut map);
+        assert_eq!(map["e"], 3);
+        assert_eq!(map["\u{E9}"], 3);
+
+        assert_eq!(cur.most_common_after(2).iter().map(|&(ref gc, n)| (gc.as_str(), n)).collect::<Vec<_>>(),
+            vec![("\u{E9}", 3), ("e\u{301}", 2)]);
+        assert_eq!(cur.most_common_after(10).len(), 3);
+        assert!(StrCursor::new_at_end(s).most_common_after(3).is_empty());
+    }
+}
@@ -39,14 +39,49 @@ See the [`StrCursor`](struct.StrCursor.html) type for details.
 
 ## Compatibility
 
-`strcursor` is currently supported on `rustc` version 1.1.0 and higher.
+`strcursor` requires `rustc` version 1.85.0 or higher.  This is also recorded as the `rust-version` in `Cargo.toml`.
 
-* `rustc` < 1.4 will use a larger, less space-efficient implementation of `GcBuf`; rather than being the same size as `Box<str>`, it will be the same size as `String`.
+* The `pattern` feature requires a nightly compiler, since the `Pattern` trait is unstable.
 
-* `rustc` < 1.1 is not supported, due to a mysterious compiler crash.
+* The `proptest` feature is subject to the minimum supported version of the `proptest` crate, which is typically much newer.
+
+The Unicode data built into this crate (the Extended_Pictographic property and decimal digit values) is from Unicode 17.0, the same version used by `unicode-segmentation` 1.13 and `unicode-properties` 0.1.4, which are the minimum versions this crate accepts.
+
+## Features
+
+The following optional features are available:
+
+* `std` (enabled by default): implements `std::error::Error` for the crate's error types.  Implies `alloc`.  Without it, the crate is `no_std`.
+
+* `alloc` (enabled by `std`): owned types which need the `alloc` crate, namely [`GcBuf`](struct.GcBuf.html), [`StrCursorBuf`](struct.StrCursorBuf.html) and [`PositionSet`](struct.PositionSet.html), along with `ToOwned` for `Gc` and every method which returns a `String` or `Cow`.  Without it, `StrCursor`, `Gc` and the iterators need only `core`.  The `normalization` and `serde` features enable `alloc`, and `render_caret` requires it in addition to `width`.
+
+* `width`: display-width aware functionality, such as [`Gc::width`](struct.Gc.html#method.width) and [`StrCursor::render_caret`](struct.StrCursor.html#method.render_caret), using the `unicode-width` crate.
+
+* `normalization`: Unicode normalization of grapheme clusters, such as [`IterAfter::normalized`](struct.IterAfter.html#method.normalized), using the `unicode-normalization` crate.
+
+* `unicode-properties`: Unicode character properties of grapheme clusters, such as [`Gc::general_category`](struct.Gc.html#method.general_category) and [`Gc::is_emoji`](struct.Gc.html#method.is_emoji), using the `unicode-properties` and `unicode-script` crates.  This may also be enabled as `properties`.
+
+* `pattern`: implements the unstable `Pattern` trait for [`Gc`](struct.Gc.html) and [`GcBuf`](struct.GcBuf.html), allowing them to be used with `str` methods such as `find` and `split`.  Requires a nightly compiler.
+
+* `serde`: implements `Serialize` for [`Gc`](struct.Gc.html) and [`GcBuf`](struct.GcBuf.html), and `Deserialize` for `GcBuf` and `&Gc`, using the `serde` crate.  Clusters are represented as strings; deserializing a string which is not exactly one grapheme cluster is an error.
+
+* `proptest`: implements `Arbitrary` for [`GcBuf`](struct.GcBuf.html), and provides [`strategy::cursor_in`](strategy/fn.cursor_in.html) for generating cursors, using the `proptest` crate.
 
 */
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "pattern", feature(pattern))]
+
+#[cfg(feature = "std")] extern crate core;
+#[cfg(any(feature = "alloc", test))] #[macro_use] extern crate alloc;
+#[cfg(all(test, not(feature = "std")))] extern crate std;
 extern crate unicode_segmentation as uniseg;
+#[cfg(feature = "normalization")] extern crate unicode_normalization;
+#[cfg(feature = "unicode-properties")] extern crate unicode_properties;
+#[cfg(feature = "unicode-properties")] extern crate unicode_script;
+#[cfg(feature = "width")] extern crate unicode_width;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(feature = "proptest")] extern crate proptest;
+#[cfg(all(test, feature = "serde"))] extern crate serde_json;
 
 /**
 Inserts a panic in debug builds, an optimisation hint in release builds.
@@ -64,11 +99,42 @@ macro_rules! debug_unreachable {
     };
 }
 
-pub use grapheme::{Gc, GcBuf};
-
+pub use bounded::BoundedCursor;
+pub use error::{ComposeError, GcParseError, NotCharError, PosError};
+#[cfg(feature = "alloc")] pub use error::FromStringError;
+#[cfg(feature = "alloc")] pub use buf::{CursorPos, PositionSet, StrCursorBuf};
+pub use grapheme::{Gc, GcInline};
+#[cfg(feature = "alloc")] pub use grapheme::GcBuf;
+pub use iter::{GraphemeChunks, GraphemeWindows, IterAfter, IterBefore, IterBetween, WithCursor};
+#[cfg(feature = "normalization")] pub use iter::Normalized;
+pub use lines::{LineStarts, LineTerminators};
+pub use search::{Occurrences, RMatchesGc, SplitNAfter};
+#[cfg(feature = "pattern")] pub use search::GcSearcher;
+pub use span::{Match, Span, SpanIter};
+#[cfg(feature = "alloc")] pub use text::reverse_graphemes;
+#[cfg(all(feature = "width", feature = "alloc"))] pub use caret::CaretOptions;
+#[cfg(feature = "width")] pub use width::WrapToWidth;
+#[cfg(feature = "unicode-properties")] pub use props::{is_mark_char, GeneralCategory, Script};
+
+mod bounded;
+pub mod bounds;
+#[cfg(feature = "alloc")] mod buf;
+#[cfg(all(feature = "width", feature = "alloc"))] mod caret;
+mod error;
 pub mod grapheme;
+mod iter;
+mod lines;
+#[cfg(feature = "unicode-properties")] mod props;
+mod search;
+#[cfg(feature = "serde")] mod serde_impls;
+#[cfg(feature = "proptest")] pub mod strategy;
+mod span;
+pub mod text;
 mod util;
+#[cfg(feature = "width")] mod width;
 
+#[cfg(any(feature = "alloc", test))] use alloc::string::String;
+#[cfg(all(test, not(feature = "std")))] use alloc::vec::Vec;
 use uniseg::UnicodeSegmentation as UniSeg;
 
 /**
@@ -90,9 +156,17 @@ This last point is somewhat important: the cursor is designed to favour operatin
 
 The methods that operate on the cursor will either return a fresh `Option<StrCursor>` (depending on whether the seek operation is valid or not), or mutate the existing cursor (in which case, they will *panic* if the seek operation is not valid).
 */
+#[derive(Clone, Copy)]
 pub struct StrCursor<'a> {
     s: &'a str,
-    at: *const u8,
+    at: usize,
+}
+
+impl StrCursor<'static> {
+    /**
+    A cursor over the empty string.
+    */
+    pub const EMPTY: StrCursor<'static> = StrCursor::new_at_start("");
 }
 
 impl<'a> StrCursor<'a> {
@@ -100,10 +174,10 @@ impl<'a> StrCursor<'a> {
     Create a new cursor at the start of `s`.
     */
     #[inline]
-    pub fn new_at_start(s: &'a str) -> StrCursor<'a> {
+    pub const fn new_at_start(s: &'a str) -> StrCursor<'a> {
         StrCursor {
-            s: s,
-            at: s.as_ptr(),
+            s,
+            at: 0,
         }
     }
 
@@ -111,11 +185,29 @@ impl<'a> StrCursor<'a> {
     Create a new cursor past at the end of `s`.
     */
     #[inline]
-    pub fn new_at_end(s: &'a str) -> StrCursor<'a> {
+    pub const fn new_at_end(s: &'a str) -> StrCursor<'a> {
         StrCursor {
-            s: s,
-            at: byte_pos_to_ptr(s, s.len()),
+            s,
+            at: s.len(),
+        }
+    }
+
+    /**
+    Create a new cursor at the given byte position, in a constant expression.
+
+    Returns `None` if `byte_pos` is past the end of `s`, or does not lie on a code point boundary.  As this can only check code point boundaries, the cursor may not lie on a grapheme cluster boundary; outside of constant expressions, prefer [`new_at_left_of_byte_pos`](#method.new_at_left_of_byte_pos).
+    */
+    #[inline]
+    pub const fn new_at_byte_pos_const(s: &'a str, byte_pos: usize) -> Option<StrCursor<'a>> {
+        let bytes = s.as_bytes();
+        // Continuation bytes are of the form `0b10xx_xxxx`.
+        if byte_pos > bytes.len() || (byte_pos < bytes.len() && bytes[byte_pos] & 0xC0 == 0x80) {
+            return None;
         }
+        Some(StrCursor {
+            s,
+            at: byte_pos,
+        })
     }
 
     /**
@@ -123,22 +215,9 @@ impl<'a> StrCursor<'a> {
     */
     #[inline]
     pub fn new_at_left_of_byte_pos(s: &'a str, byte_pos: usize) -> StrCursor<'a> {
-        // Start at a codepoint.
-        let cur = StrCursor::new_at_cp_left_of_byte_pos(s, byte_pos);
-
-        // Seek back to the previous grapheme.
-        let prev = cur.at_prev();
-
-        let prev = match prev {
-            None => return cur, // We were already at the start.
-            Some(c) => c
-        };
-
-        // unwrap should be OK here.
-        if prev.byte_pos() + prev.after().unwrap().len() > byte_pos {
-            prev
-        } else {
-            cur
+        StrCursor {
+            s,
+            at: bounds::prev_gc_boundary(s, byte_pos),
         }
     }
 
@@ -147,14 +226,30 @@ impl<'a> StrCursor<'a> {
     */
     #[inline]
     pub fn new_at_right_of_byte_pos(s: &'a str, byte_pos: usize) -> StrCursor<'a> {
-        // I don't know how robust the grapheme iteration rules are when trying to step forward from a (potentially) invalid position.  As such, I'm *instead* going to start from a known-good position.
-        let cur = StrCursor::new_at_left_of_byte_pos(s, byte_pos);
-        if cur.byte_pos() == byte_pos {
-            return cur;
+        StrCursor {
+            s,
+            at: bounds::next_gc_boundary(s, byte_pos),
         }
+    }
 
-        // This unwrap shouldn't be able to fail.
-        cur.at_next().unwrap()
+    /**
+    Create a new cursor at exactly the given byte position.
+
+    Unlike [`new_at_left_of_byte_pos`](#method.new_at_left_of_byte_pos) and [`new_at_right_of_byte_pos`](#method.new_at_right_of_byte_pos), the position is not adjusted: if it is out of bounds, or does not lie on a grapheme cluster boundary, an error is returned instead.
+    */
+    pub fn new_at_byte_pos(s: &'a str, byte_pos: usize) -> Result<StrCursor<'a>, PosError> {
+        if byte_pos > s.len() {
+            Err(PosError::OutOfBounds)
+        } else if !s.is_char_boundary(byte_pos) {
+            Err(PosError::NotCharBoundary)
+        } else if !bounds::is_gc_boundary(s, byte_pos) {
+            Err(PosError::NotGcBoundary)
+        } else {
+            Ok(StrCursor {
+                s,
+                at: byte_pos,
+            })
+        }
     }
 
     /**
@@ -167,8 +262,8 @@ impl<'a> StrCursor<'a> {
     #[inline]
     pub fn new_at_cp_left_of_byte_pos(s: &'a str, byte_pos: usize) -> StrCursor<'a> {
         StrCursor {
-            s: s,
-            at: unsafe { seek_utf8_cp_start_left(s, byte_pos_to_ptr(s, byte_pos)) },
+            s,
+            at: seek_utf8_cp_start_left(s, check_byte_pos(s, byte_pos)),
         }
     }
 
@@ -182,8 +277,29 @@ impl<'a> StrCursor<'a> {
     #[inline]
     pub fn new_at_cp_right_of_byte_pos(s: &'a str, byte_pos: usize) -> StrCursor<'a> {
         StrCursor {
-            s: s,
-            at: unsafe { seek_utf8_cp_start_right(s, byte_pos_to_ptr(s, byte_pos)) },
+            s,
+            at: seek_utf8_cp_start_right(s, check_byte_pos(s, byte_pos)),
+        }
+    }
+
+    /**
+    Create a new cursor at the given position, measured in UTF-16 code units from the beginning of the string.
+
+    This is intended for interoperating with protocols, such as the Language Server Protocol, which express positions as UTF-16 offsets.  Positions which do not fall on a grapheme cluster boundary are snapped *left*, to the start of the grapheme cluster containing them.  This includes positions between the two halves of a surrogate pair, and positions between a base code point and its combining marks.
+
+    Returns `None` if `u16_pos` is past the end of the string.
+    */
+    pub fn new_at_utf16_pos(s: &'a str, u16_pos: usize) -> Option<StrCursor<'a>> {
+        let mut units = 0;
+        for (i, cp) in s.char_indices() {
+            units += cp.len_utf16();
+            if units > u16_pos {
+                return Some(StrCursor::new_at_left_of_byte_pos(s, i));
+            }
+        }
+        match units == u16_pos {
+            true => Some(StrCursor::new_at_end(s)),
+            false => None
         }
     }
 
@@ -239,6 +355,91 @@ impl<'a> StrCursor<'a> {
         }
     }
 
+    /**
+    Returns a new cursor at the beginning of the next grapheme cluster, or `None` if the cursor is currently positioned at the end of the string.
+
+    This is identical to [`at_next`](#method.at_next), but reads better when treating cursors as the endpoints of a range.
+    */
+    #[inline]
+    pub fn successor(self) -> Option<StrCursor<'a>> {
+        self.at_next()
+    }
+
+    /**
+    Returns a new cursor at the beginning of the previous grapheme cluster, or `None` if the cursor is currently positioned at the beginning of the string.
+
+    This is identical to [`at_prev`](#method.at_prev), but reads better when treating cursors as the endpoints of a range.
+    */
+    #[inline]
+    pub fn predecessor(self) -> Option<StrCursor<'a>> {
+        self.at_prev()
+    }
+
+    /**
+    Returns the number of grapheme clusters between this cursor and `other`, regardless of which is further to the right.
+
+    Returns `None` if the cursors are from different strings (even different subsets of the same string).
+    */
+    pub fn steps_to(&self, other: &StrCursor<'a>) -> Option<usize> {
+        self.slice_between(*other)
+            .map(|s| UniSeg::graphemes(s, /*is_extended:*/true).count())
+    }
+
+    /**
+    Returns whichever of this cursor and `other` comes first.
+
+    # Panics
+
+    If the cursors are from different strings (even different subsets of the same string), they have no ordering, and this function will panic.
+    */
+    pub fn min(self, other: StrCursor<'a>) -> StrCursor<'a> {
+        match self.partial_cmp(&other) {
+            Some(core::cmp::Ordering::Greater) => other,
+            Some(_) => self,
+            None => panic!("cannot compare cursors from different strings"),
+        }
+    }
+
+    /**
+    Returns whichever of this cursor and `other` comes last.
+
+    # Panics
+
+    If the cursors are from different strings (even different subsets of the same string), they have no ordering, and this function will panic.
+    */
+    pub fn max(self, other: StrCursor<'a>) -> StrCursor<'a> {
+        match self.partial_cmp(&other) {
+            Some(core::cmp::Ordering::Less) => other,
+            Some(_) => self,
+            None => panic!("cannot compare cursors from different strings"),
+        }
+    }
+
+    /**
+    Compares the positions of this cursor and `other`, giving a total order.
+
+    For cursors into the same string, this compares their byte positions, and agrees with `partial_cmp`.  Cursors into different strings (even different subsets of the same string) are ordered by the address and length of their strings, and then by position.  This order is arbitrary, but consistent, so it can be used for sorting without panicking.
+    */
+    pub fn cmp_by_pos(&self, other: &StrCursor<'a>) -> core::cmp::Ordering {
+        (self.s.as_ptr(), self.s.len(), self.at).cmp(&(other.s.as_ptr(), other.s.len(), other.at))
+    }
+
+    /**
+    Restricts this cursor to lie between `lo` and `hi`, inclusive.
+
+    Returns `lo` if the cursor is before `lo`, `hi` if it is after `hi`, and the cursor itself otherwise.
+
+    # Panics
+
+    If any of the cursors are from different strings, or `lo` is after `hi`, then this function will panic.
+    */
+    pub fn clamp(self, lo: StrCursor<'a>, hi: StrCursor<'a>) -> StrCursor<'a> {
+        if lo.max(hi) != hi {
+            panic!("cannot clamp to a range whose start is after its end");
+        }
+        self.max(lo).min(hi)
+    }
+
     /**
     Seeks the cursor to the beginning of the previous grapheme cluster.
 
@@ -379,6 +580,54 @@ impl<'a> StrCursor<'a> {
         }
     }
 
+    /**
+    Returns the first grapheme cluster after the cursor, and a cursor past it, or `None` if the cursor is at the end of the string.
+
+    This is identical to [`next`](#method.next), but named after `slice::split_first`.
+    */
+    #[inline]
+    pub fn split_first(self) -> Option<(&'a Gc, StrCursor<'a>)> {
+        self.next()
+    }
+
+    /**
+    Returns the last grapheme cluster of the string, and a cursor before it, or `None` if the cursor is at the end of the string.
+
+    Only the text after the cursor is considered, so the returned cursor is never to the left of this one.  This is the cursor analogue of `slice::split_last`.
+    */
+    pub fn split_last(mut self) -> Option<(&'a Gc, StrCursor<'a>)> {
+        let (head, gc) = Gc::rsplit_from(self.slice_after())?;
+        self.at += head.len();
+        Some((gc, self))
+    }
+
+    /**
+    Seeks past the next grapheme cluster if it satisfies `pred`, returning it.
+
+    If the cursor is at the end of the string, or the next cluster does not satisfy `pred`, the cursor is left unchanged and `None` is returned.
+    */
+    #[inline]
+    pub fn advance_if<P>(&mut self, pred: P) -> Option<&'a Gc>
+    where P: FnOnce(&Gc) -> bool {
+        match self.after() {
+            Some(g) if pred(g) => {
+                unsafe { self.unsafe_seek_right(g.len()); }
+                Some(g)
+            },
+            _ => None
+        }
+    }
+
+    /**
+    Seeks past the next grapheme cluster if it is equal to `gc`, returning whether it did so.
+
+    See [`advance_if`](#method.advance_if).
+    */
+    #[inline]
+    pub fn advance_if_eq(&mut self, gc: &Gc) -> bool {
+        self.advance_if(|g| g == gc).is_some()
+    }
+
     /**
     Returns the grapheme cluster immediately to the left of the cursor, or `None` is the cursor is at the start of the string.
     */
@@ -395,6 +644,16 @@ impl<'a> StrCursor<'a> {
         Gc::split_from(self.slice_after()).map(|(gc, _)| gc)
     }
 
+    /**
+    Is the grapheme cluster immediately to the right of the cursor equal to `gc`, ignoring case?
+
+    Returns `false` if the cursor is at the end of the string.  See [`Gc::eq_ignore_case`](struct.Gc.html#method.eq_ignore_case).
+    */
+    #[inline]
+    pub fn after_eq_ignore_case(&self, gc: &Gc) -> bool {
+        self.after().is_some_and(|after| after.eq_ignore_case(gc))
+    }
+
     /**
     Returns the contents of the string to the left of the cursor.
     */
@@ -415,6 +674,40 @@ impl<'a> StrCursor<'a> {
         }
     }
 
+    /**
+    Returns an iterator over the bytes of the string to the left of the cursor.
+    */
+    #[inline]
+    pub fn bytes_before_iter(&self) -> ::core::str::Bytes<'a> {
+        self.slice_before().bytes()
+    }
+
+    /**
+    Returns an iterator over the bytes of the string to the right of the cursor.
+    */
+    #[inline]
+    pub fn bytes_after_iter(&self) -> ::core::str::Bytes<'a> {
+        self.slice_after().bytes()
+    }
+
+    /**
+    Returns a sub-slice of the contents of the string to the right of the cursor, given a range of byte offsets relative to the cursor.
+
+    Returns `None` if the range is reversed or extends past the end of the string, or if either end of the range does not fall on a grapheme cluster boundary.  Unlike indexing a `str` directly, this never panics.
+    */
+    pub fn slice_after_range(&self, range: ::core::ops::Range<usize>) -> Option<&'a str> {
+        let after = self.slice_after();
+        if range.start > range.end || range.end > after.len() {
+            return None;
+        }
+        let pos = self.byte_pos();
+        if bounds::is_gc_boundary(self.s, pos + range.start) && bounds::is_gc_boundary(self.s, pos + range.end) {
+            Some(&after[range])
+        } else {
+            None
+        }
+    }
+
     /**
     Returns the contents of the string *between* this cursor and another cursor.
 
@@ -425,17 +718,38 @@ impl<'a> StrCursor<'a> {
         if !str_eq_literal(self.s, until.s) {
             None
         } else {
-            use std::cmp::{max, min};
+            use core::cmp::{max, min};
             unsafe {
                 let beg = min(self.at, until.at);
                 let end = max(self.at, until.at);
-                let len = end as usize - beg as usize;
-                let bytes = ::std::slice::from_raw_parts(beg, len);
-                Some(::std::str::from_utf8_unchecked(bytes))
+                Some(self.s.get_unchecked(beg..end))
             }
         }
     }
 
+    /**
+    Returns a copy of the whole string with the region between this cursor and `end` replaced by `replacement`.
+
+    That is, the result is `self.slice_before()`, followed by `replacement`, followed by `end.slice_after()`.  This always allocates a new `String`; the original string is not modified.
+
+    Returns `None` if the cursors are from different strings (even different subsets of the same string), or if `end` is to the left of this cursor.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn splice(&self, end: StrCursor<'a>, replacement: &str) -> Option<String> {
+        if !str_eq_literal(self.s, end.s) || end.at < self.at {
+            return None;
+        }
+        let before = self.slice_before();
+        let after = end.slice_after();
+        let mut out = String::with_capacity(before.len() + replacement.len() + after.len());
+        out.push_str(before);
+        out.push_str(replacement);
+        out.push_str(after);
+        Some(out)
+    }
+
     /**
     Returns the code point immediately to the left of the cursor, or `None` is the cursor is at the start of the string.
     */
@@ -465,31 +779,197 @@ impl<'a> StrCursor<'a> {
     */
     #[inline]
     pub fn byte_pos(&self) -> usize {
-        self.at as usize - self.s.as_ptr() as usize
+        self.at
     }
 
+    /**
+    Returns the cursor's current position within the string as the number of UTF-8 code units from the cursor to the end of the string.
+
+    This is the length of [`slice_after`](#method.slice_after), and the right-anchored counterpart to [`byte_pos`](#method.byte_pos).
+    */
     #[inline]
-    fn try_seek_left_cp(&mut self) -> bool {
-        unsafe {
-            // We just have to ensure that offsetting the `at` pointer *at all* is safe.
-            if self.byte_pos() == 0 {
-                return false;
+    pub fn byte_pos_from_end(&self) -> usize {
+        self.s.len() - self.byte_pos()
+    }
+
+    /**
+    Returns the cursor's current position within the string as the number of UTF-16 code units from the beginning of the string.
+
+    This is the inverse of [`new_at_utf16_pos`](#method.new_at_utf16_pos).  It requires a scan of the string before the cursor.
+    */
+    pub fn utf16_pos(&self) -> usize {
+        self.slice_before().encode_utf16().count()
+    }
+
+    /**
+    Is this cursor at the same logical position as `other`, in a string with the same contents?
+
+    Unlike `==`, which requires both cursors to refer to the *same* string, this compares the text on either side of each cursor by value.  This is useful for comparing cursors over equal strings in different buffers.
+    */
+    #[inline]
+    pub fn eq_text_pos(&self, other: &StrCursor) -> bool {
+        self.byte_pos() == other.byte_pos() && self.s == other.s
+    }
+
+    /**
+    Renders up to `context` grapheme clusters either side of the cursor, with a `‸` marking the cursor's position.
+
+    If there is more text beyond the rendered clusters on either side, this is indicated with `…`.  Grapheme clusters are never split.  This is intended as a diagnostic aid for inspecting a cursor in a large string.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn debug_caret(&self, context: usize) -> String {
+        let (start, end) = self.context_window(context);
+        let mut out = String::new();
+        if start.at_prev().is_some() {
+            out.push('…');
+        }
+        out.push_str(start.slice_between(*self).unwrap_or(""));
+        out.push('‸');
+        out.push_str(self.slice_between(end).unwrap_or(""));
+        if end.at_next().is_some() {
+            out.push('…');
+        }
+        out
+    }
+
+    /**
+    Renders up to `context` grapheme clusters either side of the cursor as with [`debug_caret`](#method.debug_caret), but with every cluster escaped as by [`Gc::escape_debug`](struct.Gc.html#method.escape_debug).
+
+    This makes invisible code points, such as zero width joiners, variation selectors and bidirectional controls, visible in the output.
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn debug_window(&self, context: usize) -> String {
+        use core::fmt::Write;
+        let (start, end) = self.context_window(context);
+        let mut out = String::new();
+        if start.at_prev().is_some() {
+            out.push('…');
+        }
+        for gc in start.iter_between(*self) {
+            let _ = write!(out, "{}", gc.display_escaped());
+        }
+        out.push('‸');
+        for gc in self.iter_between(end) {
+            let _ = write!(out, "{}", gc.display_escaped());
+        }
+        if end.at_next().is_some() {
+            out.push('…');
+        }
+        out
+    }
+
+    /**
+    Returns the text covering up to `n` grapheme clusters immediately before the cursor, and up to `n` immediately after it.
+
+    Fewer clusters are included on either side if the cursor is near the start or end of the string.  Only the clusters within the window are examined, so this is cheap even in very large strings.  See also [`surrounding_span`](#method.surrounding_span).
+    */
+    pub fn surrounding(&self, n: usize) -> (&'a str, &'a str) {
+        let (start, end) = self.context_window(n);
+        (&self.s[start.at..self.at], &self.s[self.at..end.at])
+    }
+
+    /**
+    Returns cursors up to `context` grapheme clusters to the left and right of this cursor.
+    */
+    fn context_window(&self, context: usize) -> (StrCursor<'a>, StrCursor<'a>) {
+        let mut before = self.iter_before();
+        let mut after = self.iter_after();
+        let start = before.by_ref().take(context).last().map(|_| before.cursor()).unwrap_or(*self);
+        let end = after.by_ref().take(context).last().map(|_| after.cursor()).unwrap_or(*self);
+        (start, end)
+    }
+
+    /**
+    Returns a new cursor advanced past any whitespace grapheme clusters immediately to the right of the cursor.
+
+    A grapheme cluster is considered whitespace if *all* of its code points are whitespace.
+    */
+    #[inline]
+    pub fn skip_whitespace_after(mut self) -> StrCursor<'a> {
+        while let Some((gc, next)) = self.next() {
+            if !gc.is_whitespace() {
+                break;
             }
-            self.at = seek_utf8_cp_start_left(self.s, self.at.offset(-1));
-            true
+            self = next;
         }
+        self
     }
 
+    /**
+    Returns a new cursor moved back before any whitespace grapheme clusters immediately to the left of the cursor.
+
+    A grapheme cluster is considered whitespace if *all* of its code points are whitespace.
+    */
     #[inline]
-    fn try_seek_right_cp(&mut self) -> bool {
-        unsafe {
-            // We just have to ensure that offsetting the `at` pointer *at all* is safe.
-            if self.byte_pos() == self.s.len() {
-                return false;
+    pub fn skip_whitespace_before(mut self) -> StrCursor<'a> {
+        while let Some((gc, prev)) = self.prev() {
+            if !gc.is_whitespace() {
+                break;
+            }
+            self = prev;
+        }
+        self
+    }
+
+    /**
+    Returns a new cursor at the beginning of the next non-whitespace grapheme cluster at or to the right of the cursor, or `None` if there are only whitespace clusters remaining.
+
+    This is [`skip_whitespace_after`](#method.skip_whitespace_after), except that reaching the end of the string is treated as failure.
+    */
+    #[inline]
+    pub fn at_non_whitespace(self) -> Option<StrCursor<'a>> {
+        let cur = self.skip_whitespace_after();
+        if cur.byte_pos() == cur.s.len() {
+            None
+        } else {
+            Some(cur)
+        }
+    }
+
+    /**
+    Trims grapheme clusters matching `pred` from both ends of the text to the right of the cursor.
+
+    Returns the remaining text, along with cursors at its start and end.  This is the grapheme cluster analogue of `str::trim_matches`, applied to [`slice_after`](#method.slice_after).  Clusters are tested left to right from the cursor, then right to left from the end of the string, stopping at the first non-matching cluster in each direction; if every cluster matches, both cursors are at the end of the string.
+    */
+    pub fn trim_matches<P>(self, mut pred: P) -> (&'a str, StrCursor<'a>, StrCursor<'a>)
+    where P: FnMut(&Gc) -> bool {
+        let mut start = self;
+        while let Some((gc, next)) = start.next() {
+            if !pred(gc) {
+                break;
+            }
+            start = next;
+        }
+        let mut end = StrCursor::new_at_end(self.s);
+        while end.at > start.at {
+            match end.prev() {
+                Some((gc, prev)) if pred(gc) => end = prev,
+                _ => break
             }
-            self.at = seek_utf8_cp_start_right(self.s, self.at.offset(1));
-            true
         }
+        (start.slice_between(end).unwrap_or(""), start, end)
+    }
+
+    #[inline]
+    fn try_seek_left_cp(&mut self) -> bool {
+        if self.at == 0 {
+            return false;
+        }
+        self.at = seek_utf8_cp_start_left(self.s, self.at - 1);
+        true
+    }
+
+    #[inline]
+    fn try_seek_right_cp(&mut self) -> bool {
+        if self.at == self.s.len() {
+            return false;
+        }
+        self.at = seek_utf8_cp_start_right(self.s, self.at + 1);
+        true
     }
 
     #[inline]
@@ -500,9 +980,7 @@ impl<'a> StrCursor<'a> {
         };
         match len {
             Some(len) => {
-                unsafe {
-                    self.at = self.at.offset(-(len as isize));
-                }
+                self.at -= len;
                 true
             },
             None => false
@@ -517,9 +995,7 @@ impl<'a> StrCursor<'a> {
         };
         match len {
             Some(len) => {
-                unsafe {
-                    self.at = self.at.offset(len as isize);
-                }
+                self.at += len;
                 true
             },
             None => false
@@ -531,7 +1007,7 @@ impl<'a> StrCursor<'a> {
     */
     #[inline]
     pub unsafe fn unsafe_seek_left(&mut self, bytes: usize) {
-        self.at = self.at.offset(-(bytes as isize));
+        self.at -= bytes;
     }
 
     /**
@@ -539,7 +1015,7 @@ impl<'a> StrCursor<'a> {
     */
     #[inline]
     pub unsafe fn unsafe_seek_right(&mut self, bytes: usize) {
-        self.at = self.at.offset(bytes as isize);
+        self.at += bytes;
     }
 
     /**
@@ -547,20 +1023,12 @@ impl<'a> StrCursor<'a> {
     */
     #[inline]
     pub unsafe fn unsafe_set_at(&mut self, s: &'a str) {
-        self.at = s.as_bytes().as_ptr();
+        self.at = s.as_ptr() as usize - self.s.as_ptr() as usize;
     }
 }
 
-impl<'a> Copy for StrCursor<'a> {}
-
-impl<'a> Clone for StrCursor<'a> {
-    fn clone(&self) -> StrCursor<'a> {
-        *self
-    }
-}
-
-impl<'a> std::fmt::Debug for StrCursor<'a> {
-	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+impl<'a> core::fmt::Debug for StrCursor<'a> {
+	fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         write!(fmt, "StrCursor({:?} | {:?})", self.slice_before(), self.slice_after())
     }
 }
@@ -582,7 +1050,7 @@ impl<'a> PartialEq for StrCursor<'a> {
 }
 
 impl<'a> PartialOrd for StrCursor<'a> {
-    fn partial_cmp(&self, other: &StrCursor<'a>) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &StrCursor<'a>) -> Option<core::cmp::Ordering> {
         // If the cursors are from different strings, they are unordered.
         if (self.s.as_ptr() != other.s.as_ptr()) || (self.s.len() != other.s.len()) {
             None
@@ -592,15 +1060,162 @@ impl<'a> PartialOrd for StrCursor<'a> {
     }
 }
 
-impl<'a> std::hash::Hash for StrCursor<'a> {
+/**
+Sorts cursors by their position, using [`StrCursor::cmp_by_pos`](struct.StrCursor.html#method.cmp_by_pos).
+
+The cursors should all be from the same string; in debug builds, this is asserted.  Otherwise, cursors from different strings are grouped together by string.
+*/
+pub fn sort_cursors(cursors: &mut [StrCursor]) {
+    debug_assert!(cursors.windows(2).all(|w| str_eq_literal(w[0].s, w[1].s)),
+        "cannot sort cursors from different strings");
+    cursors.sort_unstable_by(StrCursor::cmp_by_pos);
+}
+
+impl<'a> core::hash::Hash for StrCursor<'a> {
     fn hash<H>(&self, state: &mut H)
-    where H: std::hash::Hasher {
+    where H: core::hash::Hasher {
         self.s.as_ptr().hash(state);
         self.s.len().hash(state);
         self.at.hash(state);
     }
 }
 
+#[cfg(test)]
+#[test]
+fn test_unicode_version() {
+    // The built-in tables are checked against these crates, so they must all agree with the version given in the "Compatibility" section.
+    assert_eq!(uniseg::UNICODE_VERSION, (17, 0, 0));
+    #[cfg(feature = "unicode-properties")]
+    assert_eq!(unicode_properties::UNICODE_VERSION, (17, 0, 0));
+}
+
+#[cfg(test)]
+#[test]
+fn test_min_max_clamp() {
+    let s = "noe\u{308}l 💪!";
+    let a = StrCursor::new_at_left_of_byte_pos(s, 1);
+    let b = StrCursor::new_at_left_of_byte_pos(s, 6);
+    let c = StrCursor::new_at_left_of_byte_pos(s, 11);
+    assert_eq!((a.min(b), a.max(b)), (a, b));
+    assert_eq!((b.min(a), b.max(a)), (a, b));
+    assert_eq!((a.min(a), a.max(a)), (a, a));
+    assert_eq!(a.clamp(b, c), b);
+    assert_eq!(b.clamp(a, c), b);
+    assert_eq!(c.clamp(a, b), b);
+    assert_eq!(c.clamp(c, c), c);
+}
+
+#[cfg(test)]
+#[test]
+fn test_sort_cursors() {
+    use core::cmp::Ordering;
+    let s = "noe\u{308}l 💪 e\u{301}!";
+    let mut curs: Vec<_> = StrCursor::new_at_start(s).iter_after().with_cursor()
+        .map(|(cur, _)| cur)
+        .collect();
+    curs.push(StrCursor::new_at_end(s));
+    let mut shuffled = curs.clone();
+    shuffled.reverse();
+    shuffled.rotate_left(curs.len() / 3);
+    assert!(shuffled != curs);
+    sort_cursors(&mut shuffled);
+    assert_eq!(shuffled, curs);
+    assert!(shuffled.windows(2).all(|w| w[0].byte_pos() < w[1].byte_pos()));
+
+    let a = curs[1];
+    assert_eq!(a.cmp_by_pos(&a), Ordering::Equal);
+    assert_eq!(a.cmp_by_pos(&curs[2]), Ordering::Less);
+    assert_eq!(curs[2].cmp_by_pos(&a), Ordering::Greater);
+
+    // Different strings are ordered consistently, rather than panicking.
+    let b = StrCursor::new_at_start(&s[..3]);
+    assert!(a.cmp_by_pos(&b) != Ordering::Equal);
+    assert_eq!(a.cmp_by_pos(&b), b.cmp_by_pos(&a).reverse());
+    let mut mixed = [a, b, curs[0]];
+    mixed.sort_by(StrCursor::cmp_by_pos);
+    assert_eq!(mixed.iter().filter(|c| c.slice_all() == s).count(), 2);
+}
+
+#[cfg(all(test, debug_assertions))]
+#[test]
+#[should_panic]
+fn test_sort_cursors_different_strings() {
+    let s = "abc";
+    sort_cursors(&mut [StrCursor::new_at_end(s), StrCursor::new_at_start(&s[..2])]);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic]
+fn test_min_different_strings() {
+    let s = "abc";
+    StrCursor::new_at_start(s).min(StrCursor::new_at_start(&s[..2]));
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic]
+fn test_clamp_inverted() {
+    let s = "abc";
+    StrCursor::new_at_start(s).clamp(StrCursor::new_at_end(s), StrCursor::new_at_start(s));
+}
+
+#[cfg(test)]
+#[test]
+fn test_hash_eq_contract() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use core::hash::{Hash, Hasher};
+
+    fn hash(cur: &StrCursor) -> u64 {
+        let mut h = DefaultHasher::new();
+        cur.hash(&mut h);
+        h.finish()
+    }
+
+    for s in &["", "abc", "noe\u{308}l 💪!", "\u{1F468}\u{200D}\u{1F469}x\r\n\u{1F1E6}\u{1F1FA}"] {
+        let s: &str = s;
+        let mut map = HashMap::new();
+        let mut cur = StrCursor::new_at_start(s);
+        loop {
+            let copy = cur;
+            #[allow(clippy::clone_on_copy)]
+            let clone = cur.clone();
+            assert_eq!(cur, cur);
+            assert_eq!((cur, hash(&cur)), (copy, hash(&copy)));
+            assert_eq!((cur, hash(&cur)), (clone, hash(&clone)));
+
+            // The same buffer, reached through a full-range reslice.
+            let whole = StrCursor::new_at_left_of_byte_pos(&s[0..], cur.byte_pos());
+            assert_eq!((cur, hash(&cur)), (whole, hash(&whole)));
+
+            // A proper subslice is a different string, even at the same address.
+            let sub_len = s.char_indices().next_back().map_or(0, |(i, _)| i);
+            if cur.byte_pos() <= sub_len && !s.is_empty() {
+                let sub = StrCursor::new_at_left_of_byte_pos(&s[..sub_len], cur.byte_pos());
+                assert!(cur != sub);
+                assert_eq!(cur.partial_cmp(&sub), None);
+            }
+
+            if let Some(next) = cur.at_next() {
+                let back = next.at_prev().unwrap();
+                assert_eq!((cur, hash(&cur)), (back, hash(&back)));
+            }
+            if let Some(prev) = cur.at_prev() {
+                let back = prev.at_next().unwrap();
+                assert_eq!((cur, hash(&cur)), (back, hash(&back)));
+            }
+
+            assert_eq!(map.insert(cur, cur.byte_pos()), None);
+            assert_eq!(map.get(&clone), Some(&cur.byte_pos()));
+            cur = match cur.at_next() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    }
+}
+
 #[cfg(test)]
 #[test]
 fn test_new_at_start() {
@@ -609,6 +1224,65 @@ fn test_new_at_start() {
     assert_eq!(cur.slice_after(), "abcdef");
 }
 
+#[cfg(test)]
+#[test]
+fn test_utf16_pos() {
+    // "a", "e" + U+0301, U+1F4AA (a surrogate pair), "!".
+    let s = "ae\u{301}\u{1F4AA}!";
+    let pos = |u| StrCursor::new_at_utf16_pos(s, u).map(|cur| cur.byte_pos());
+    assert_eq!(pos(0), Some(0));
+    assert_eq!(pos(1), Some(1));
+    // Between the base and its combining mark.
+    assert_eq!(pos(2), Some(1));
+    assert_eq!(pos(3), Some(4));
+    // Between the two halves of the surrogate pair.
+    assert_eq!(pos(4), Some(4));
+    assert_eq!(pos(5), Some(8));
+    assert_eq!(pos(6), Some(9));
+    assert_eq!(pos(7), None);
+
+    let mut cur = StrCursor::new_at_start(s);
+    let mut units = vec![cur.utf16_pos()];
+    while let Some((_, next)) = cur.next() {
+        cur = next;
+        units.push(cur.utf16_pos());
+    }
+    assert_eq!(units, [0, 1, 3, 5, 6]);
+    for &u in &units {
+        assert_eq!(StrCursor::new_at_utf16_pos(s, u).unwrap().utf16_pos(), u);
+    }
+
+    assert_eq!(StrCursor::new_at_utf16_pos("", 0), Some(StrCursor::new_at_start("")));
+    assert_eq!(StrCursor::new_at_utf16_pos("", 1), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_const_constructors() {
+    static FIXTURE: &str = "noe\u{308}l";
+    static START: StrCursor<'static> = StrCursor::new_at_start(FIXTURE);
+    static END: StrCursor<'static> = StrCursor::new_at_end(FIXTURE);
+    static TABLE: [Option<StrCursor<'static>>; 4] = [
+        StrCursor::new_at_byte_pos_const(FIXTURE, 2),
+        StrCursor::new_at_byte_pos_const(FIXTURE, 4),
+        StrCursor::new_at_byte_pos_const(FIXTURE, 6),
+        StrCursor::new_at_byte_pos_const(FIXTURE, 7),
+    ];
+    const EMPTY: StrCursor<'static> = StrCursor::EMPTY;
+
+    assert_eq!(START, StrCursor::new_at_start(FIXTURE));
+    assert_eq!(END, StrCursor::new_at_end(FIXTURE));
+    assert_eq!(TABLE[0], Some(StrCursor::new_at_left_of_byte_pos(FIXTURE, 2)));
+    assert_eq!(TABLE[0].unwrap().after().map(Gc::as_str), Some("e\u{308}"));
+    // Byte 4 is the middle of the diaeresis.
+    assert_eq!(TABLE[1], None);
+    assert_eq!(TABLE[2], Some(END));
+    assert_eq!(TABLE[3], None);
+    assert_eq!(EMPTY.slice_all(), "");
+    assert_eq!(EMPTY.byte_pos(), 0);
+    assert_eq!(EMPTY.after(), None);
+}
+
 #[cfg(test)]
 #[test]
 fn test_new_at_end() {
@@ -878,6 +1552,30 @@ fn test_next() {
     ]);
 }
 
+#[cfg(test)]
+#[test]
+fn test_split_first_last() {
+    let s = "ae\u{301}\u{1F468}\u{200D}\u{1F469}";
+    let cur = StrCursor::new_at_start(s);
+    assert_eq!(cur.split_first(), cur.next());
+    let (gc, rest) = cur.split_first().unwrap();
+    assert_eq!((gc.as_str(), rest.byte_pos()), ("a", 1));
+
+    let (gc, init) = cur.split_last().unwrap();
+    assert_eq!(gc, "\u{1F468}\u{200D}\u{1F469}");
+    assert_eq!(init.slice_before(), "ae\u{301}");
+    let (gc, init) = init.prev().unwrap();
+    assert_eq!(gc, "e\u{301}");
+    let (gc, last) = init.split_last().unwrap();
+    assert_eq!(gc, "\u{1F468}\u{200D}\u{1F469}");
+    assert_eq!(last.next(), Some((gc, StrCursor::new_at_end(s))));
+
+    let end = StrCursor::new_at_end(s);
+    assert_eq!(end.split_first(), None);
+    assert_eq!(end.split_last(), None);
+    assert_eq!(StrCursor::new_at_start("x").split_last().map(|(gc, cur)| (gc.as_str(), cur.byte_pos())), Some(("x", 0)));
+}
+
 #[cfg(test)]
 #[test]
 fn test_next_cp() {
@@ -944,20 +1642,246 @@ fn test_slice_between() {
     assert_eq!(cur0.slice_between(cur3), None);
 }
 
+#[cfg(test)]
+#[test]
+fn test_bytes_iter() {
+    let s = "ae\u{301}b";
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 4);
+    assert_eq!(cur.bytes_before_iter().collect::<Vec<_>>(), vec![b'a', b'e', 0xcc, 0x81]);
+    assert_eq!(cur.bytes_after_iter().collect::<Vec<_>>(), vec![b'b']);
+    assert_eq!(cur.bytes_before_iter().next_back(), Some(0x81));
+    assert_eq!(StrCursor::new_at_end(s).bytes_after_iter().next(), None);
+    assert_eq!(StrCursor::new_at_start(s).bytes_before_iter().next(), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_slice_after_range() {
+    let s = "xnoe\u{308}l \u{1F4AA}";
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 1);
+    assert_eq!(cur.slice_after_range(0..2), Some("no"));
+    assert_eq!(cur.slice_after_range(1..5), Some("oe\u{308}"));
+    assert_eq!(cur.slice_after_range(2..11), Some("e\u{308}l \u{1F4AA}"));
+    assert_eq!(cur.slice_after_range(5..5), Some(""));
+    assert_eq!(cur.slice_after_range(3..3), None);
+    assert_eq!(cur.slice_after_range(2..3), None);
+    assert_eq!(cur.slice_after_range(2..4), None);
+    assert_eq!(cur.slice_after_range(7..9), None);
+    assert_eq!(cur.slice_after_range(0..12), None);
+    assert_eq!(cur.slice_after_range(12..12), None);
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 2..1;
+    assert_eq!(cur.slice_after_range(reversed), None);
+    assert_eq!(StrCursor::new_at_end(s).slice_after_range(0..0), Some(""));
+}
+
+#[cfg(test)]
+#[test]
+fn test_successor_predecessor() {
+    let s = "noe\u{308}l 💪";
+    let beg = StrCursor::new_at_start(s);
+    let end = StrCursor::new_at_end(s);
+    assert_eq!(beg.successor(), beg.at_next());
+    assert_eq!(end.successor(), None);
+    assert_eq!(end.predecessor(), end.at_prev());
+    assert_eq!(beg.predecessor(), None);
+    assert_eq!(beg.steps_to(&end), Some(6));
+    assert_eq!(end.steps_to(&beg), Some(6));
+    assert_eq!(beg.steps_to(&beg), Some(0));
+    assert_eq!(beg.successor().unwrap().steps_to(&end.predecessor().unwrap()), Some(4));
+    assert_eq!(beg.steps_to(&StrCursor::new_at_end(&s[1..])), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_new_at_byte_pos() {
+    let s = "noe\u{308}l 💪";
+    assert_eq!(StrCursor::new_at_byte_pos(s, 0), Ok(StrCursor::new_at_start(s)));
+    assert_eq!(StrCursor::new_at_byte_pos(s, 2).map(|c| c.slice_after()), Ok("e\u{308}l 💪"));
+    assert_eq!(StrCursor::new_at_byte_pos(s, 3), Err(PosError::NotGcBoundary));
+    assert_eq!(StrCursor::new_at_byte_pos(s, 4), Err(PosError::NotCharBoundary));
+    assert_eq!(StrCursor::new_at_byte_pos(s, 5).map(|c| c.slice_after()), Ok("l 💪"));
+    assert_eq!(StrCursor::new_at_byte_pos(s, 8), Err(PosError::NotCharBoundary));
+    assert_eq!(StrCursor::new_at_byte_pos(s, s.len()), Ok(StrCursor::new_at_end(s)));
+    assert_eq!(StrCursor::new_at_byte_pos(s, s.len() + 1), Err(PosError::OutOfBounds));
+    assert_eq!(StrCursor::new_at_byte_pos("", 0), Ok(StrCursor::new_at_start("")));
+}
+
+#[cfg(test)]
+#[test]
+fn test_advance_if() {
+    let s = "-e\u{301}1";
+    let mut cur = StrCursor::new_at_start(s);
+    assert_eq!(cur.advance_if(|g| g == "+"), None);
+    assert_eq!(cur.byte_pos(), 0);
+    assert_eq!(cur.advance_if(|g| g == "-").map(Gc::as_str), Some("-"));
+    assert_eq!(cur.byte_pos(), 1);
+    assert!(!cur.advance_if_eq(Gc::from_str("e").unwrap()));
+    assert_eq!(cur.byte_pos(), 1);
+    assert!(cur.advance_if_eq(Gc::from_str("e\u{301}").unwrap()));
+    assert_eq!(cur.byte_pos(), 4);
+    assert_eq!(cur.advance_if(|g| g.base_char().is_ascii_digit()).map(Gc::as_str), Some("1"));
+    assert_eq!(cur.advance_if(|_| true), None);
+    assert_eq!(cur.byte_pos(), s.len());
+}
+
+#[cfg(test)]
+#[test]
+fn test_after_eq_ignore_case() {
+    let s = "Stra\u{DF}e";
+    let cur = StrCursor::new_at_start(s);
+    assert!(cur.after_eq_ignore_case(Gc::from_str("s").unwrap()));
+    assert!(!cur.after_eq_ignore_case(Gc::from_str("t").unwrap()));
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 4);
+    assert!(cur.after_eq_ignore_case(Gc::from_str("\u{1E9E}").unwrap()));
+    assert!(!StrCursor::new_at_end(s).after_eq_ignore_case(Gc::from_str("e").unwrap()));
+}
+
+#[cfg(all(test, feature = "alloc"))]
+#[test]
+fn test_splice() {
+    let s = "they hit, fight, kick";
+    let cur0 = StrCursor::new_at_left_of_byte_pos(s, 5);
+    let cur1 = StrCursor::new_at_left_of_byte_pos(s, 8);
+    assert_eq!(cur0.splice(cur1, "punch"), Some("they punch, fight, kick".into()));
+    assert_eq!(cur0.splice(cur0, "never "), Some("they never hit, fight, kick".into()));
+    assert_eq!(cur0.splice(StrCursor::new_at_end(s), ""), Some("they ".into()));
+    assert_eq!(StrCursor::new_at_start(s).splice(StrCursor::new_at_end(s), "noe\u{308}l"),
+        Some("noe\u{308}l".into()));
+    assert_eq!(cur1.splice(cur0, "punch"), None);
+    assert_eq!(cur0.splice(StrCursor::new_at_end(&s[1..]), "punch"), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_byte_pos_from_end() {
+    let s = "noe\u{308}l 💪";
+    let mut cur = StrCursor::new_at_start(s);
+    assert_eq!(cur.byte_pos_from_end(), s.len());
+    while let Some(next) = cur.at_next() {
+        cur = next;
+        assert_eq!(cur.byte_pos_from_end(), cur.slice_after().len());
+        assert_eq!(cur.byte_pos() + cur.byte_pos_from_end(), s.len());
+    }
+    assert_eq!(StrCursor::new_at_end(s).byte_pos_from_end(), 0);
+    assert_eq!(StrCursor::new_at_start("").byte_pos_from_end(), 0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_eq_text_pos() {
+    let s = "they hit, fight, kick";
+    let t = String::from(s);
+    let cur0 = StrCursor::new_at_left_of_byte_pos(s, 5);
+    let cur1 = StrCursor::new_at_left_of_byte_pos(&t, 5);
+    assert!(cur0 != cur1);
+    assert!(cur0.eq_text_pos(&cur1));
+    assert!(cur1.eq_text_pos(&cur0));
+    assert!(!cur0.eq_text_pos(&cur1.at_next().unwrap()));
+    assert!(!cur0.eq_text_pos(&StrCursor::new_at_left_of_byte_pos(&t[..20], 5)));
+    assert!(!cur0.eq_text_pos(&StrCursor::new_at_left_of_byte_pos("they hit, fight, kicl", 5)));
+}
+
+#[cfg(all(test, feature = "alloc"))]
+#[test]
+fn test_debug_caret() {
+    let s = "they noe\u{308}l 💪 kick";
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 10);
+    assert_eq!(cur.debug_caret(2), "…oe\u{308}‸l …");
+    assert_eq!(cur.debug_caret(4), "… noe\u{308}‸l 💪 …");
+    assert_eq!(cur.debug_caret(100), "they noe\u{308}‸l 💪 kick");
+    assert_eq!(cur.debug_caret(0), "…‸…");
+    assert_eq!(StrCursor::new_at_start(s).debug_caret(3), "‸the…");
+    assert_eq!(StrCursor::new_at_end(s).debug_caret(3), "…ick‸");
+    assert_eq!(StrCursor::new_at_start("").debug_caret(3), "‸");
+}
+
+#[cfg(test)]
+#[test]
+fn test_trim_matches() {
+    let s = "x-- e\u{301}-\u{1F4AA} -\u{301}-";
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 1);
+    let (inner, start, end) = cur.trim_matches(|gc| gc == "-" || gc == " ");
+    assert_eq!(inner, "e\u{301}-\u{1F4AA} -\u{301}");
+    assert_eq!((start.byte_pos(), end.byte_pos()), (4, s.len() - 1));
+    assert_eq!(start.slice_between(end), Some(inner));
+
+    // The cursor itself bounds the left side.
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 7);
+    assert_eq!(cur.trim_matches(|gc| gc == "-").0, "\u{1F4AA} -\u{301}");
+
+    let (inner, start, end) = StrCursor::new_at_start("---").trim_matches(|gc| gc == "-");
+    assert_eq!(inner, "");
+    assert_eq!((start.byte_pos(), end.byte_pos()), (3, 3));
+    assert_eq!(StrCursor::new_at_start("abc").trim_matches(|_| false).0, "abc");
+}
+
+#[cfg(all(test, feature = "alloc"))]
+#[test]
+fn test_debug_window() {
+    let s = "ab\u{1F468}\u{200D}\u{1F469}e\u{301}\u{202E}cd";
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 13);
+    assert_eq!(cur.debug_window(1), "…\u{1F468}\\u{200d}\u{1F469}‸e\\u{301}…");
+    assert_eq!(cur.debug_window(2), "…b\u{1F468}\\u{200d}\u{1F469}‸e\\u{301}\\u{202e}…");
+    assert_eq!(cur.debug_window(0), "…‸…");
+    assert_eq!(StrCursor::new_at_end(s).debug_window(100),
+        "ab\u{1F468}\\u{200d}\u{1F469}e\\u{301}\\u{202e}cd‸");
+}
+
+#[cfg(test)]
+#[test]
+fn test_surrounding() {
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    let s = format!("ab{}e\u{301}{}cd", family, family);
+    let s = &*s;
+
+    let start = StrCursor::new_at_start(s);
+    assert_eq!(start.surrounding(2), ("", "ab"));
+    assert_eq!(start.surrounding(0), ("", ""));
+    let end = StrCursor::new_at_end(s);
+    assert_eq!(end.surrounding(3), (&*format!("{}cd", family), ""));
+    assert_eq!(end.surrounding(100), (s, ""));
+
+    // Between "e\u{301}" and the second family.
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 2 + family.len() + 3);
+    assert_eq!(cur.surrounding(1), ("e\u{301}", family));
+    assert_eq!(cur.surrounding(2), (&*format!("{}e\u{301}", family), &*format!("{}c", family)));
+    assert_eq!(cur.surrounding(4), (&*format!("ab{}e\u{301}", family), &*format!("{}cd", family)));
+}
+
+#[cfg(test)]
+#[test]
+fn test_skip_whitespace() {
+    let s = "  \t\r\nlet\u{a0} \u{362}x  ";
+    let cur = StrCursor::new_at_start(s);
+    assert_eq!(cur.skip_whitespace_after().slice_after(), "let\u{a0} \u{362}x  ");
+    assert_eq!(cur.at_non_whitespace().map(|c| c.byte_pos()), Some(5));
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 8);
+    assert_eq!(cur.skip_whitespace_after().slice_after(), " \u{362}x  ");
+    assert_eq!(cur.skip_whitespace_before(), cur);
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 10);
+    assert_eq!(cur.skip_whitespace_before().slice_before(), "  \t\r\nlet");
+    let cur = StrCursor::new_at_end(s);
+    assert_eq!(cur.skip_whitespace_before().slice_after(), "  ");
+    assert_eq!(cur.skip_whitespace_after(), cur);
+    assert_eq!(cur.at_non_whitespace(), None);
+    assert_eq!(StrCursor::new_at_start(" \n ").at_non_whitespace(), None);
+}
+
 #[inline]
-fn byte_pos_to_ptr(s: &str, byte_pos: usize) -> *const u8 {
+fn check_byte_pos(s: &str, byte_pos: usize) -> usize {
     if s.len() < byte_pos {
         panic!("byte position out of bounds: the len is {} but the position is {}",
             s.len(), byte_pos);
     }
-    unsafe { s.as_ptr().offset(byte_pos as isize) }
+    byte_pos
 }
 
 #[inline]
-unsafe fn seek_utf8_cp_start_left(s: &str, mut from: *const u8) -> *const u8 {
-    let beg = s.as_ptr();
-    while from > beg && (*from & 0b11_00_0000 == 0b10_00_0000) {
-        from = from.offset(-1);
+fn seek_utf8_cp_start_left(s: &str, mut from: usize) -> usize {
+    let b = s.as_bytes();
+    while from > 0 && from < b.len() && (b[from] & 0b11_00_0000 == 0b10_00_0000) {
+        from -= 1;
     }
     from
 }
@@ -966,20 +1890,19 @@ unsafe fn seek_utf8_cp_start_left(s: &str, mut from: *const u8) -> *const u8 {
 #[test]
 fn test_seek_utf8_cp_start_left() {
     let s = "カブム！";
-    let b = s.as_bytes();
-    assert_eq!(unsafe { seek_utf8_cp_start_left(s, &b[0]) }, &b[0]);
-    assert_eq!(unsafe { seek_utf8_cp_start_left(s, &b[1]) }, &b[0]);
-    assert_eq!(unsafe { seek_utf8_cp_start_left(s, &b[2]) }, &b[0]);
-    assert_eq!(unsafe { seek_utf8_cp_start_left(s, &b[3]) }, &b[3]);
-    assert_eq!(unsafe { seek_utf8_cp_start_left(s, &b[4]) }, &b[3]);
-    assert_eq!(unsafe { seek_utf8_cp_start_left(s, &b[5]) }, &b[3]);
+    assert_eq!(seek_utf8_cp_start_left(s, 0), 0);
+    assert_eq!(seek_utf8_cp_start_left(s, 1), 0);
+    assert_eq!(seek_utf8_cp_start_left(s, 2), 0);
+    assert_eq!(seek_utf8_cp_start_left(s, 3), 3);
+    assert_eq!(seek_utf8_cp_start_left(s, 4), 3);
+    assert_eq!(seek_utf8_cp_start_left(s, 5), 3);
 }
 
 #[inline]
-unsafe fn seek_utf8_cp_start_right(s: &str, mut from: *const u8) -> *const u8 {
-    let end = s.as_ptr().offset(s.len() as isize);
-    while from < end && (*from & 0b11_00_0000 == 0b10_00_0000) {
-        from = from.offset(1);
+fn seek_utf8_cp_start_right(s: &str, mut from: usize) -> usize {
+    let b = s.as_bytes();
+    while from < b.len() && (b[from] & 0b11_00_0000 == 0b10_00_0000) {
+        from += 1;
     }
     from
 }
@@ -988,13 +1911,12 @@ unsafe fn seek_utf8_cp_start_right(s: &str, mut from: *const u8) -> *const u8 {
 #[test]
 fn test_seek_utf8_cp_start_right() {
     let s = "カブム！";
-    let b = s.as_bytes();
-    assert_eq!(unsafe { seek_utf8_cp_start_right(s, &b[0]) }, &b[0]);
-    assert_eq!(unsafe { seek_utf8_cp_start_right(s, &b[1]) }, &b[3]);
-    assert_eq!(unsafe { seek_utf8_cp_start_right(s, &b[2]) }, &b[3]);
-    assert_eq!(unsafe { seek_utf8_cp_start_right(s, &b[3]) }, &b[3]);
-    assert_eq!(unsafe { seek_utf8_cp_start_right(s, &b[4]) }, &b[6]);
-    assert_eq!(unsafe { seek_utf8_cp_start_right(s, &b[5]) }, &b[6]);
+    assert_eq!(seek_utf8_cp_start_right(s, 0), 0);
+    assert_eq!(seek_utf8_cp_start_right(s, 1), 3);
+    assert_eq!(seek_utf8_cp_start_right(s, 2), 3);
+    assert_eq!(seek_utf8_cp_start_right(s, 3), 3);
+    assert_eq!(seek_utf8_cp_start_right(s, 4), 6);
+    assert_eq!(seek_utf8_cp_start_right(s, 5), 6);
 }
 
 #[inline]
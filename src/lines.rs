@@ -0,0 +1,599 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Line-oriented cursor methods, and the helpers they share.
+
+Line terminators are recognised at the grapheme cluster level.  Because `"\r\n"` is always segmented as a single cluster, it naturally counts as a single terminator.
+*/
+use core::ops::BitOr;
+use grapheme::Gc;
+use uniseg::UnicodeSegmentation as UniSeg;
+use StrCursor;
+
+/**
+A set of line terminators, used to configure the line-aware methods of [`StrCursor`](struct.StrCursor.html).
+
+Individual terminators can be combined using `|`.  The default set recognises `"\n"`, `"\r\n"` and `"\r"`.
+
+Terminators are matched against whole grapheme clusters.  Because the cursor cannot be positioned between the `"\r"` and `"\n"` of a `"\r\n"` pair, such a pair is treated as a single terminator if *any* of `CR`, `LF` or `CRLF` is in the set.
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct LineTerminators(u8);
+
+impl LineTerminators {
+    /// U+000A LINE FEED.
+    pub const LF: LineTerminators = LineTerminators(1 << 0);
+    /// U+000D CARRIAGE RETURN.
+    pub const CR: LineTerminators = LineTerminators(1 << 1);
+    /// U+000D CARRIAGE RETURN followed by U+000A LINE FEED.
+    pub const CRLF: LineTerminators = LineTerminators(1 << 2);
+    /// U+0085 NEXT LINE.
+    pub const NEL: LineTerminators = LineTerminators(1 << 3);
+    /// U+2028 LINE SEPARATOR.
+    pub const LS: LineTerminators = LineTerminators(1 << 4);
+    /// U+2029 PARAGRAPH SEPARATOR.
+    pub const PS: LineTerminators = LineTerminators(1 << 5);
+    /// U+000B LINE TABULATION.
+    pub const VT: LineTerminators = LineTerminators(1 << 6);
+    /// U+000C FORM FEED.
+    pub const FF: LineTerminators = LineTerminators(1 << 7);
+
+    /**
+    An empty set: nothing terminates a line.
+    */
+    pub fn none() -> LineTerminators {
+        LineTerminators(0)
+    }
+
+    /**
+    Only `"\n"`.
+    */
+    pub fn unix() -> LineTerminators {
+        LineTerminators::LF
+    }
+
+    /**
+    Only `"\r\n"`.
+    */
+    pub fn windows() -> LineTerminators {
+        LineTerminators::CRLF
+    }
+
+    /**
+    The line terminators recommended by the Unicode standard: `"\n"`, `"\r\n"`, `"\r"`, NEL (U+0085), LS (U+2028) and PS (U+2029).
+    */
+    pub fn unicode() -> LineTerminators {
+        LineTerminators::unix() | LineTerminators::CR | LineTerminators::CRLF
+            | LineTerminators::NEL | LineTerminators::LS | LineTerminators::PS
+    }
+
+    /**
+    Every supported line terminator: those in [`unicode`](#method.unicode), plus vertical tab and form feed.
+    */
+    pub fn all() -> LineTerminators {
+        LineTerminators::unicode() | LineTerminators::VT | LineTerminators::FF
+    }
+
+    /**
+    Does this set contain every terminator in `other`?
+    */
+    pub fn contains(self, other: LineTerminators) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /**
+    Is the given grapheme cluster a line terminator in this set?
+    */
+    pub fn is_terminator(self, gc: &Gc) -> bool {
+        let flag = match gc.as_str() {
+            "\n" => LineTerminators::LF,
+            "\r" => LineTerminators::CR,
+            "\r\n" => LineTerminators::CRLF | LineTerminators::CR | LineTerminators::LF,
+            "\u{85}" => LineTerminators::NEL,
+            "\u{2028}" => LineTerminators::LS,
+            "\u{2029}" => LineTerminators::PS,
+            "\u{b}" => LineTerminators::VT,
+            "\u{c}" => LineTerminators::FF,
+            _ => return false
+        };
+        self.0 & flag.0 != 0
+    }
+}
+
+impl Default for LineTerminators {
+    fn default() -> LineTerminators {
+        LineTerminators::LF | LineTerminators::CR | LineTerminators::CRLF
+    }
+}
+
+impl BitOr for LineTerminators {
+    type Output = LineTerminators;
+
+    fn bitor(self, other: LineTerminators) -> LineTerminators {
+        LineTerminators(self.0 | other.0)
+    }
+}
+
+/**
+An iterator over cursors at the start of each line of a string, returned from [`StrCursor::iter_line_starts`](struct.StrCursor.html#method.iter_line_starts).
+*/
+#[derive(Clone, Debug)]
+pub struct LineStarts<'a> {
+    next: Option<StrCursor<'a>>,
+    terms: LineTerminators,
+}
+
+impl<'a> Iterator for LineStarts<'a> {
+    type Item = StrCursor<'a>;
+
+    fn next(&mut self) -> Option<StrCursor<'a>> {
+        let start = self.next?;
+        let mut cur = start;
+        self.next = None;
+        while let Some((gc, next)) = cur.next() {
+            if self.terms.is_terminator(gc) {
+                self.next = Some(next);
+                break;
+            }
+            cur = next;
+        }
+        Some(start)
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns an iterator over cursors at the start of each line of `s`.
+
+    The first line always starts at the beginning of `s`, so this yields at least one cursor.  If `s` ends with a line terminator, the last cursor is at the end of `s`, at the start of an empty final line; this is consistent with [`line_col`](#method.line_col).
+
+    `"\n"`, `"\r\n"` and `"\r"` are all treated as line terminators.
+    */
+    pub fn iter_line_starts(s: &'a str) -> LineStarts<'a> {
+        StrCursor::iter_line_starts_with(s, LineTerminators::default())
+    }
+
+    /**
+    Returns an iterator over cursors at the start of each line of `s`, recognising only the given line terminators.
+
+    See [`iter_line_starts`](#method.iter_line_starts).
+    */
+    pub fn iter_line_starts_with(s: &'a str, terms: LineTerminators) -> LineStarts<'a> {
+        LineStarts {
+            next: Some(StrCursor::new_at_start(s)),
+            terms,
+        }
+    }
+
+    /**
+    Create a new cursor at the given 1-based line and column of `s`.
+
+    The column is measured in grapheme clusters, exactly as returned by [`line_col`](#method.line_col).  A column one past the last cluster on the line (*i.e.* just before the line terminator, if any) is allowed.
+
+    Returns `None` if the line does not exist, or if the column is zero or beyond the end of the line.
+    */
+    pub fn new_at_line_col(s: &'a str, line: usize, col: usize) -> Option<StrCursor<'a>> {
+        StrCursor::new_at_line_col_with(s, line, col, LineTerminators::default())
+    }
+
+    /**
+    Create a new cursor at the given 1-based line and column of `s`, recognising only the given line terminators.
+
+    See [`new_at_line_col`](#method.new_at_line_col).
+    */
+    pub fn new_at_line_col_with(s: &'a str, line: usize, col: usize, terms: LineTerminators) -> Option<StrCursor<'a>> {
+        if col == 0 {
+            return None;
+        }
+        let mut cur = nth_line_start(s, line, terms)?;
+        for _ in 1..col {
+            match cur.next() {
+                Some((gc, next)) if !terms.is_terminator(gc) => cur = next,
+                _ => return None
+            }
+        }
+        Some(cur)
+    }
+
+    /**
+    Create a new cursor at the given 1-based line and column of `s`, where the column is measured in bytes.
+
+    This is the inverse of [`line_col_bytes`](#method.line_col_bytes).  Returns `None` if the line does not exist, if the column is zero or beyond the end of the line, or if the column does not lie on a grapheme cluster boundary.
+    */
+    pub fn new_at_line_col_bytes(s: &'a str, line: usize, col: usize) -> Option<StrCursor<'a>> {
+        StrCursor::new_at_line_col_bytes_with(s, line, col, LineTerminators::default())
+    }
+
+    /**
+    Create a new cursor at the given 1-based line and byte column of `s`, recognising only the given line terminators.
+
+    See [`new_at_line_col_bytes`](#method.new_at_line_col_bytes).
+    */
+    pub fn new_at_line_col_bytes_with(s: &'a str, line: usize, col: usize, terms: LineTerminators) -> Option<StrCursor<'a>> {
+        if col == 0 {
+            return None;
+        }
+        let mut cur = nth_line_start(s, line, terms)?;
+        let byte_pos = cur.byte_pos().checked_add(col - 1)?;
+        while cur.byte_pos() < byte_pos {
+            match cur.next() {
+                Some((gc, next)) if !terms.is_terminator(gc) => cur = next,
+                _ => return None
+            }
+        }
+        if cur.byte_pos() == byte_pos {
+            Some(cur)
+        } else {
+            None
+        }
+    }
+
+    /**
+    Returns the 1-based line and column numbers of the cursor.
+
+    The column is measured in grapheme clusters since the start of the line.  `"\n"`, `"\r\n"` and `"\r"` are all treated as (single) line terminators; a cursor positioned immediately after a terminator is in column 1 of the next line.
+
+    This requires scanning the entire string before the cursor.
+    */
+    pub fn line_col(&self) -> (usize, usize) {
+        self.line_col_with(LineTerminators::default())
+    }
+
+    /**
+    Returns the 1-based line and column numbers of the cursor, recognising only the given line terminators.
+
+    See [`line_col`](#method.line_col).
+    */
+    pub fn line_col_with(&self, terms: LineTerminators) -> (usize, usize) {
+        let (line, offset) = line_and_offset(*self, terms);
+        (line, offset + 1)
+    }
+
+    /**
+    Returns the 1-based line and column numbers of the cursor, where the column is measured in bytes since the start of the line.
+
+    See [`line_col`](#method.line_col).
+    */
+    pub fn line_col_bytes(&self) -> (usize, usize) {
+        self.line_col_bytes_with(LineTerminators::default())
+    }
+
+    /**
+    Returns the 1-based line and byte column numbers of the cursor, recognising only the given line terminators.
+
+    See [`line_col_bytes`](#method.line_col_bytes).
+    */
+    pub fn line_col_bytes_with(&self, terms: LineTerminators) -> (usize, usize) {
+        let (line, _) = line_and_offset(*self, terms);
+        (line, self.byte_pos() - line_start(*self, terms).byte_pos() + 1)
+    }
+
+    /**
+    Moves the cursor to the start of the current line; that is, immediately after the previous line terminator, or to the start of the string.
+
+    `"\n"`, `"\r\n"` and `"\r"` are all treated as line terminators.
+    */
+    pub fn seek_line_start(&mut self) {
+        self.seek_line_start_with(LineTerminators::default());
+    }
+
+    /**
+    Moves the cursor to the start of the current line, recognising only the given line terminators.
+
+    See [`seek_line_start`](#method.seek_line_start).
+    */
+    pub fn seek_line_start_with(&mut self, terms: LineTerminators) {
+        *self = line_start(*self, terms);
+    }
+
+    /**
+    Moves the cursor to the end of the current line; that is, immediately *before* the next line terminator, or to the end of the string.
+
+    `"\n"`, `"\r\n"` and `"\r"` are all treated as line terminators, so the cursor is placed before the `"\r"` of a `"\r\n"` pair.
+    */
+    pub fn seek_line_end(&mut self) {
+        self.seek_line_end_with(LineTerminators::default());
+    }
+
+    /**
+    Moves the cursor to the end of the current line, recognising only the given line terminators.
+
+    See [`seek_line_end`](#method.seek_line_end).
+    */
+    pub fn seek_line_end_with(&mut self, terms: LineTerminators) {
+        *self = line_end(*self, terms);
+    }
+
+    /**
+    Returns the line containing the cursor, along with the byte offsets of its start and end.
+
+    The line does not include its terminator.  If the cursor is immediately after a line terminator, it is considered to be at the start of the following line.  The last line of the string need not be terminated.
+
+    `"\n"`, `"\r\n"` and `"\r"` are all treated as line terminators.
+    */
+    pub fn current_line(&self) -> (&'a str, usize, usize) {
+        self.current_line_with(LineTerminators::default())
+    }
+
+    /**
+    Returns the line containing the cursor, along with the byte offsets of its start and end, recognising only the given line terminators.
+
+    See [`current_line`](#method.current_line).
+    */
+    pub fn current_line_with(&self, terms: LineTerminators) -> (&'a str, usize, usize) {
+        let start = line_start(*self, terms);
+        let end = line_end(*self, terms);
+        (&self.s[start.byte_pos()..end.byte_pos()], start.byte_pos(), end.byte_pos())
+    }
+}
+
+/**
+Returns a cursor at the start of the line containing `cur`.
+*/
+pub fn line_start(mut cur: StrCursor, terms: LineTerminators) -> StrCursor {
+    while let Some((gc, prev)) = cur.prev() {
+        if terms.is_terminator(gc) {
+            break;
+        }
+        cur = prev;
+    }
+    cur
+}
+
+/**
+Returns a cursor at the start of the given 1-based line of `s`, or `None` if there is no such line.
+*/
+pub fn nth_line_start(s: &str, line: usize, terms: LineTerminators) -> Option<StrCursor<'_>> {
+    if line == 0 {
+        return None;
+    }
+    let mut cur = StrCursor::new_at_start(s);
+    let mut at_line = 1;
+    while at_line < line {
+        let (gc, next) = cur.next()?;
+        if terms.is_terminator(gc) {
+            at_line += 1;
+        }
+        cur = next;
+    }
+    Some(cur)
+}
+
+/**
+Returns a cursor at the end of the line containing `cur`, *before* any line terminator.
+*/
+pub fn line_end(mut cur: StrCursor, terms: LineTerminators) -> StrCursor {
+    while let Some((gc, next)) = cur.next() {
+        if terms.is_terminator(gc) {
+            break;
+        }
+        cur = next;
+    }
+    cur
+}
+
+/**
+Returns the 1-based line number and the 0-based cluster offset of `cur` within its line.
+*/
+pub fn line_and_offset(cur: StrCursor, terms: LineTerminators) -> (usize, usize) {
+    let mut line = 1;
+    let mut offset = 0;
+    for gr in UniSeg::graphemes(cur.slice_before(), /*is_extended:*/true) {
+        if terms.is_terminator(unsafe { Gc::from_str_unchecked(gr) }) {
+            line += 1;
+            offset = 0;
+        } else {
+            offset += 1;
+        }
+    }
+    (line, offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::LineTerminators;
+    use StrCursor;
+
+    fn at(s: &str, byte_pos: usize) -> StrCursor<'_> {
+        StrCursor::new_at_left_of_byte_pos(s, byte_pos)
+    }
+
+    #[test]
+    fn test_line_col() {
+        let s = "ab\ncd\r\n\ref\n";
+        let r = (0..s.len()+1).map(|i| at(s, i))
+            .map(|cur| (cur.byte_pos(), cur.line_col(), cur.line_col_bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(r, vec![
+            (0, (1, 1), (1, 1)),
+            (1, (1, 2), (1, 2)),
+            (2, (1, 3), (1, 3)),
+            (3, (2, 1), (2, 1)),
+            (4, (2, 2), (2, 2)),
+            (5, (2, 3), (2, 3)),
+            (5, (2, 3), (2, 3)),
+            (7, (3, 1), (3, 1)),
+            (8, (4, 1), (4, 1)),
+            (9, (4, 2), (4, 2)),
+            (10, (4, 3), (4, 3)),
+            (11, (5, 1), (5, 1)),
+        ]);
+    }
+
+    #[test]
+    fn test_line_col_at_end() {
+        assert_eq!(StrCursor::new_at_end("").line_col(), (1, 1));
+        assert_eq!(StrCursor::new_at_end("abc").line_col(), (1, 4));
+        assert_eq!(StrCursor::new_at_end("abc\n").line_col(), (2, 1));
+        assert_eq!(StrCursor::new_at_end("abc\r\n").line_col_bytes(), (2, 1));
+    }
+
+    #[test]
+    fn test_new_at_line_col() {
+        let s = "ab\n\r\nnoe\u{308}l\r\n";
+        let r = (0..6).flat_map(|line| (0..7).map(move |col| (line, col)))
+            .filter_map(|(line, col)| StrCursor::new_at_line_col(s, line, col)
+                .map(|cur| (line, col, cur.byte_pos())))
+            .collect::<Vec<_>>();
+        assert_eq!(r, vec![
+            (1, 1, 0),
+            (1, 2, 1),
+            (1, 3, 2),
+            (2, 1, 3),
+            (3, 1, 5),
+            (3, 2, 6),
+            (3, 3, 7),
+            (3, 4, 10),
+            (3, 5, 11),
+            (4, 1, 13),
+        ]);
+        assert_eq!(StrCursor::new_at_line_col_bytes(s, 3, 3).map(|c| c.byte_pos()), Some(7));
+        assert_eq!(StrCursor::new_at_line_col_bytes(s, 3, 4), None);
+        assert_eq!(StrCursor::new_at_line_col_bytes(s, 3, 6).map(|c| c.byte_pos()), Some(10));
+        assert_eq!(StrCursor::new_at_line_col_bytes(s, 3, 8), None);
+        assert_eq!(StrCursor::new_at_line_col_bytes(s, 3, usize::MAX), None);
+        assert_eq!(StrCursor::new_at_line_col_bytes(s, 1, usize::MAX), None);
+    }
+
+    #[test]
+    fn test_line_col_round_trip() {
+        let s = "Jäger,Jäger\r\n\n大嫌い,💪❤!\nnoe\u{308}l\rカブム！\n";
+        let mut cur = Some(StrCursor::new_at_start(s));
+        let mut seen = 0;
+        while let Some(c) = cur {
+            let (line, col) = c.line_col();
+            assert_eq!(StrCursor::new_at_line_col(s, line, col), Some(c));
+            let (line, col) = c.line_col_bytes();
+            assert_eq!(StrCursor::new_at_line_col_bytes(s, line, col), Some(c));
+            seen += 1;
+            cur = c.at_next();
+        }
+        assert_eq!(seen, 32);
+    }
+
+    #[test]
+    fn test_line_terminators() {
+        let s = "a\u{85}b\u{2028}c\u{2029}d\ne\r\nf\rg\u{c}h";
+        let lines = |terms| {
+            let mut r = vec![];
+            let mut cur = Some(StrCursor::new_at_start(s));
+            while let Some(c) = cur {
+                if c.after().map(|gc| gc.base_char().is_ascii_alphabetic()) == Some(true) {
+                    r.push(c.line_col_with(terms));
+                }
+                cur = c.at_next();
+            }
+            r
+        };
+        assert_eq!(lines(LineTerminators::unix()), vec![
+            (1, 1), (1, 3), (1, 5), (1, 7), (2, 1), (3, 1), (3, 3), (3, 5)]);
+        assert_eq!(lines(LineTerminators::windows()), vec![
+            (1, 1), (1, 3), (1, 5), (1, 7), (1, 9), (2, 1), (2, 3), (2, 5)]);
+        assert_eq!(lines(LineTerminators::default()), vec![
+            (1, 1), (1, 3), (1, 5), (1, 7), (2, 1), (3, 1), (4, 1), (4, 3)]);
+        assert_eq!(lines(LineTerminators::unicode()), vec![
+            (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1), (7, 1), (7, 3)]);
+        assert_eq!(lines(LineTerminators::all()), vec![
+            (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1), (7, 1), (8, 1)]);
+        assert_eq!(lines(LineTerminators::none()), vec![
+            (1, 1), (1, 3), (1, 5), (1, 7), (1, 9), (1, 11), (1, 13), (1, 15)]);
+
+        assert_eq!(StrCursor::new_at_line_col_with(s, 3, 1, LineTerminators::unicode())
+            .and_then(|c| c.after()).map(|gc| gc.as_str()), Some("c"));
+        assert_eq!(StrCursor::new_at_line_col_with(s, 3, 1, LineTerminators::unix())
+            .and_then(|c| c.after()).map(|gc| gc.as_str()), Some("f"));
+        assert_eq!(StrCursor::new_at_line_col_bytes_with(s, 1, 5, LineTerminators::unix())
+            .and_then(|c| c.after()).map(|gc| gc.as_str()), Some("\u{2028}"));
+        assert_eq!(StrCursor::new_at_line_col_bytes_with(s, 1, 5, LineTerminators::unicode()), None);
+        assert!(LineTerminators::all().contains(LineTerminators::unicode()));
+        assert!(!LineTerminators::unix().contains(LineTerminators::default()));
+    }
+
+    #[test]
+    fn test_seek_line_start_end() {
+        let s = "one\ntwo e\u{301}\r\nthree\rfour";
+        let mut cur = at(s, 6);
+        cur.seek_line_start();
+        assert_eq!(cur.byte_pos(), 4);
+        cur.seek_line_start();
+        assert_eq!(cur.byte_pos(), 4);
+        cur.seek_line_end();
+        assert_eq!(cur.slice_after(), "\r\nthree\rfour");
+        cur.seek_line_end();
+        assert_eq!(cur.slice_after(), "\r\nthree\rfour");
+
+        let mut cur = at(s, 0);
+        cur.seek_line_end();
+        assert_eq!(cur.byte_pos(), 3);
+        cur.seek_line_start();
+        assert_eq!(cur.byte_pos(), 0);
+
+        let mut cur = StrCursor::new_at_end(s);
+        cur.seek_line_start();
+        assert_eq!(cur.slice_after(), "four");
+        cur.seek_line_end();
+        assert_eq!(cur, StrCursor::new_at_end(s));
+
+        // Only recognise "\n": a lone "\r" is part of the line.
+        let mut cur = StrCursor::new_at_end(s);
+        cur.seek_line_start_with(LineTerminators::unix());
+        assert_eq!(cur.slice_after(), "three\rfour");
+        let mut cur = at(s, 15);
+        cur.seek_line_end_with(LineTerminators::unix());
+        assert_eq!(cur, StrCursor::new_at_end(s));
+    }
+
+    #[test]
+    fn test_iter_line_starts() {
+        let starts = |s, terms| StrCursor::iter_line_starts_with(s, terms)
+            .map(|cur| cur.byte_pos()).collect::<Vec<_>>();
+        let s = "one\ntwo\r\n\rfour";
+        assert_eq!(starts(s, LineTerminators::default()), vec![0, 4, 9, 10]);
+        assert_eq!(starts(s, LineTerminators::unix()), vec![0, 4, 9]);
+        assert_eq!(starts("a\n", LineTerminators::default()), vec![0, 2]);
+        assert_eq!(starts("", LineTerminators::default()), vec![0]);
+        assert_eq!(starts("\n\n", LineTerminators::default()), vec![0, 1, 2]);
+
+        for cur in StrCursor::iter_line_starts(s) {
+            assert_eq!(cur.line_col().1, 1);
+            assert_eq!(cur.current_line().1, cur.byte_pos());
+        }
+    }
+
+    #[test]
+    fn test_current_line() {
+        let s = "one\ntwo e\u{301}\r\nthree\rfour";
+        assert_eq!(at(s, 0).current_line(), ("one", 0, 3));
+        assert_eq!(at(s, 3).current_line(), ("one", 0, 3));
+        assert_eq!(at(s, 4).current_line(), ("two e\u{301}", 4, 11));
+        assert_eq!(at(s, 10).current_line(), ("two e\u{301}", 4, 11));
+        assert_eq!(at(s, 13).current_line(), ("three", 13, 18));
+        assert_eq!(StrCursor::new_at_end(s).current_line(), ("four", 19, 23));
+        assert_eq!(StrCursor::new_at_end(s).current_line_with(LineTerminators::unix()),
+            ("three\rfour", 13, 23));
+
+        let s = "a\n";
+        assert_eq!(StrCursor::new_at_end(s).current_line(), ("", 2, 2));
+        assert_eq!(StrCursor::new_at_start("").current_line(), ("", 0, 0));
+    }
+
+    #[test]
+    fn test_line_col_emoji() {
+        let s = "x\n💪❤ noe\u{308}l!";
+        let cur = StrCursor::new_at_end(s);
+        assert_eq!(cur.line_col(), (2, 9));
+        assert_eq!(cur.line_col_bytes(), (2, 16));
+        let cur = at(s, 9);
+        assert_eq!(cur.after().map(|gc| gc.as_str()), Some(" "));
+        assert_eq!(cur.line_col(), (2, 3));
+        assert_eq!(cur.line_col_bytes(), (2, 8));
+    }
+}
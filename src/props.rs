@@ -0,0 +1,233 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Unicode character properties of grapheme clusters.
+*/
+use unicode_properties::GeneralCategory as Ucd;
+
+/**
+The Unicode Script of a code point, re-exported from the `unicode-script` crate.
+
+See [`Gc::script`](struct.Gc.html#method.script).
+*/
+pub use unicode_script::Script;
+
+/**
+The Unicode General_Category of a code point.
+
+Variants are named using the standard two-letter abbreviations.  See [`Gc::general_category`](struct.Gc.html#method.general_category).
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum GeneralCategory {
+    /// Uppercase letter.
+    Lu,
+    /// Lowercase letter.
+    Ll,
+    /// Titlecase letter.
+    Lt,
+    /// Modifier letter.
+    Lm,
+    /// Other letter.
+    Lo,
+    /// Nonspacing mark.
+    Mn,
+    /// Spacing mark.
+    Mc,
+    /// Enclosing mark.
+    Me,
+    /// Decimal number.
+    Nd,
+    /// Letter number.
+    Nl,
+    /// Other number.
+    No,
+    /// Connector punctuation.
+    Pc,
+    /// Dash punctuation.
+    Pd,
+    /// Open punctuation.
+    Ps,
+    /// Close punctuation.
+    Pe,
+    /// Initial quote punctuation.
+    Pi,
+    /// Final quote punctuation.
+    Pf,
+    /// Other punctuation.
+    Po,
+    /// Math symbol.
+    Sm,
+    /// Currency symbol.
+    Sc,
+    /// Modifier symbol.
+    Sk,
+    /// Other symbol.
+    So,
+    /// Space separator.
+    Zs,
+    /// Line separator.
+    Zl,
+    /// Paragraph separator.
+    Zp,
+    /// Control.
+    Cc,
+    /// Format.
+    Cf,
+    /// Surrogate.
+    Cs,
+    /// Private use.
+    Co,
+    /// Unassigned.
+    Cn,
+}
+
+impl GeneralCategory {
+    /**
+    Returns the General_Category of a code point.
+    */
+    pub fn of(c: char) -> GeneralCategory {
+        use self::GeneralCategory::*;
+        match ::unicode_properties::UnicodeGeneralCategory::general_category(c) {
+            Ucd::UppercaseLetter => Lu,
+            Ucd::LowercaseLetter => Ll,
+            Ucd::TitlecaseLetter => Lt,
+            Ucd::ModifierLetter => Lm,
+            Ucd::OtherLetter => Lo,
+            Ucd::NonspacingMark => Mn,
+            Ucd::SpacingMark => Mc,
+            Ucd::EnclosingMark => Me,
+            Ucd::DecimalNumber => Nd,
+            Ucd::LetterNumber => Nl,
+            Ucd::OtherNumber => No,
+            Ucd::ConnectorPunctuation => Pc,
+            Ucd::DashPunctuation => Pd,
+            Ucd::OpenPunctuation => Ps,
+            Ucd::ClosePunctuation => Pe,
+            Ucd::InitialPunctuation => Pi,
+            Ucd::FinalPunctuation => Pf,
+            Ucd::OtherPunctuation => Po,
+            Ucd::MathSymbol => Sm,
+            Ucd::CurrencySymbol => Sc,
+            Ucd::ModifierSymbol => Sk,
+            Ucd::OtherSymbol => So,
+            Ucd::SpaceSeparator => Zs,
+            Ucd::LineSeparator => Zl,
+            Ucd::ParagraphSeparator => Zp,
+            Ucd::Control => Cc,
+            Ucd::Format => Cf,
+            Ucd::Surrogate => Cs,
+            Ucd::PrivateUse => Co,
+            Ucd::Unassigned => Cn,
+        }
+    }
+
+    /**
+    Is this a letter category (`Lu`, `Ll`, `Lt`, `Lm`, or `Lo`)?
+    */
+    pub fn is_letter(self) -> bool {
+        use self::GeneralCategory::*;
+        matches!(self, Lu | Ll | Lt | Lm | Lo)
+    }
+
+    /**
+    Is this a mark category (`Mn`, `Mc`, or `Me`)?
+    */
+    pub fn is_mark(self) -> bool {
+        use self::GeneralCategory::*;
+        matches!(self, Mn | Mc | Me)
+    }
+
+    /**
+    Is this a punctuation category (`Pc`, `Pd`, `Ps`, `Pe`, `Pi`, `Pf`, or `Po`)?
+    */
+    pub fn is_punctuation(self) -> bool {
+        use self::GeneralCategory::*;
+        matches!(self, Pc | Pd | Ps | Pe | Pi | Pf | Po)
+    }
+
+    /**
+    Is this a symbol category (`Sm`, `Sc`, `Sk`, or `So`)?
+    */
+    pub fn is_symbol(self) -> bool {
+        use self::GeneralCategory::*;
+        matches!(self, Sm | Sc | Sk | So)
+    }
+}
+
+/**
+Is a code point a combining mark (General_Category `Mn`, `Mc`, or `Me`)?
+
+Requires the `unicode-properties` feature.
+*/
+pub fn is_mark_char(c: char) -> bool {
+    GeneralCategory::of(c).is_mark()
+}
+
+/**
+The code points with the Extended_Pictographic property, as sorted, non-overlapping inclusive ranges.
+
+This is from the Unicode version given in the crate's "Compatibility" documentation, and `test_extended_pictographic_table` checks it against the segmentation rules of `unicode-segmentation`.
+*/
+const EXTENDED_PICTOGRAPHIC: &[(char, char)] = &[
+    ('\u{A9}', '\u{A9}'), ('\u{AE}', '\u{AE}'), ('\u{203C}', '\u{203C}'), ('\u{2049}', '\u{2049}'),
+    ('\u{2122}', '\u{2122}'), ('\u{2139}', '\u{2139}'), ('\u{2194}', '\u{2199}'), ('\u{21A9}', '\u{21AA}'),
+    ('\u{231A}', '\u{231B}'), ('\u{2328}', '\u{2328}'), ('\u{23CF}', '\u{23CF}'), ('\u{23E9}', '\u{23F3}'),
+    ('\u{23F8}', '\u{23FA}'), ('\u{24C2}', '\u{24C2}'), ('\u{25AA}', '\u{25AB}'), ('\u{25B6}', '\u{25B6}'),
+    ('\u{25C0}', '\u{25C0}'), ('\u{25FB}', '\u{25FE}'), ('\u{2600}', '\u{2604}'), ('\u{260E}', '\u{260E}'),
+    ('\u{2611}', '\u{2611}'), ('\u{2614}', '\u{2615}'), ('\u{2618}', '\u{2618}'), ('\u{261D}', '\u{261D}'),
+    ('\u{2620}', '\u{2620}'), ('\u{2622}', '\u{2623}'), ('\u{2626}', '\u{2626}'), ('\u{262A}', '\u{262A}'),
+    ('\u{262E}', '\u{262F}'), ('\u{2638}', '\u{263A}'), ('\u{2640}', '\u{2640}'), ('\u{2642}', '\u{2642}'),
+    ('\u{2648}', '\u{2653}'), ('\u{265F}', '\u{2660}'), ('\u{2663}', '\u{2663}'), ('\u{2665}', '\u{2666}'),
+    ('\u{2668}', '\u{2668}'), ('\u{267B}', '\u{267B}'), ('\u{267E}', '\u{267F}'), ('\u{2692}', '\u{2697}'),
+    ('\u{2699}', '\u{2699}'), ('\u{269B}', '\u{269C}'), ('\u{26A0}', '\u{26A1}'), ('\u{26A7}', '\u{26A7}'),
+    ('\u{26AA}', '\u{26AB}'), ('\u{26B0}', '\u{26B1}'), ('\u{26BD}', '\u{26BE}'), ('\u{26C4}', '\u{26C5}'),
+    ('\u{26C8}', '\u{26C8}'), ('\u{26CE}', '\u{26CF}'), ('\u{26D1}', '\u{26D1}'), ('\u{26D3}', '\u{26D4}'),
+    ('\u{26E9}', '\u{26EA}'), ('\u{26F0}', '\u{26F5}'), ('\u{26F7}', '\u{26FA}'), ('\u{26FD}', '\u{26FD}'),
+    ('\u{2702}', '\u{2702}'), ('\u{2705}', '\u{2705}'), ('\u{2708}', '\u{270D}'), ('\u{270F}', '\u{270F}'),
+    ('\u{2712}', '\u{2712}'), ('\u{2714}', '\u{2714}'), ('\u{2716}', '\u{2716}'), ('\u{271D}', '\u{271D}'),
+    ('\u{2721}', '\u{2721}'), ('\u{2728}', '\u{2728}'), ('\u{2733}', '\u{2734}'), ('\u{2744}', '\u{2744}'),
+    ('\u{2747}', '\u{2747}'), ('\u{274C}', '\u{274C}'), ('\u{274E}', '\u{274E}'), ('\u{2753}', '\u{2755}'),
+    ('\u{2757}', '\u{2757}'), ('\u{2763}', '\u{2764}'), ('\u{2795}', '\u{2797}'), ('\u{27A1}', '\u{27A1}'),
+    ('\u{27B0}', '\u{27B0}'), ('\u{27BF}', '\u{27BF}'), ('\u{2934}', '\u{2935}'), ('\u{2B05}', '\u{2B07}'),
+    ('\u{2B1B}', '\u{2B1C}'), ('\u{2B50}', '\u{2B50}'), ('\u{2B55}', '\u{2B55}'), ('\u{3030}', '\u{3030}'),
+    ('\u{303D}', '\u{303D}'), ('\u{3297}', '\u{3297}'), ('\u{3299}', '\u{3299}'), ('\u{1F004}', '\u{1F004}'),
+    ('\u{1F02C}', '\u{1F02F}'), ('\u{1F094}', '\u{1F09F}'), ('\u{1F0AF}', '\u{1F0B0}'), ('\u{1F0C0}', '\u{1F0C0}'),
+    ('\u{1F0CF}', '\u{1F0D0}'), ('\u{1F0F6}', '\u{1F0FF}'), ('\u{1F170}', '\u{1F171}'), ('\u{1F17E}', '\u{1F17F}'),
+    ('\u{1F18E}', '\u{1F18E}'), ('\u{1F191}', '\u{1F19A}'), ('\u{1F1AE}', '\u{1F1E5}'), ('\u{1F201}', '\u{1F20F}'),
+    ('\u{1F21A}', '\u{1F21A}'), ('\u{1F22F}', '\u{1F22F}'), ('\u{1F232}', '\u{1F23A}'), ('\u{1F23C}', '\u{1F23F}'),
+    ('\u{1F249}', '\u{1F25F}'), ('\u{1F266}', '\u{1F321}'), ('\u{1F324}', '\u{1F393}'), ('\u{1F396}', '\u{1F397}'),
+    ('\u{1F399}', '\u{1F39B}'), ('\u{1F39E}', '\u{1F3F0}'), ('\u{1F3F3}', '\u{1F3F5}'), ('\u{1F3F7}', '\u{1F3FA}'),
+    ('\u{1F400}', '\u{1F4FD}'), ('\u{1F4FF}', '\u{1F53D}'), ('\u{1F549}', '\u{1F54E}'), ('\u{1F550}', '\u{1F567}'),
+    ('\u{1F56F}', '\u{1F570}'), ('\u{1F573}', '\u{1F57A}'), ('\u{1F587}', '\u{1F587}'), ('\u{1F58A}', '\u{1F58D}'),
+    ('\u{1F590}', '\u{1F590}'), ('\u{1F595}', '\u{1F596}'), ('\u{1F5A4}', '\u{1F5A5}'), ('\u{1F5A8}', '\u{1F5A8}'),
+    ('\u{1F5B1}', '\u{1F5B2}'), ('\u{1F5BC}', '\u{1F5BC}'), ('\u{1F5C2}', '\u{1F5C4}'), ('\u{1F5D1}', '\u{1F5D3}'),
+    ('\u{1F5DC}', '\u{1F5DE}'), ('\u{1F5E1}', '\u{1F5E1}'), ('\u{1F5E3}', '\u{1F5E3}'), ('\u{1F5E8}', '\u{1F5E8}'),
+    ('\u{1F5EF}', '\u{1F5EF}'), ('\u{1F5F3}', '\u{1F5F3}'), ('\u{1F5FA}', '\u{1F64F}'), ('\u{1F680}', '\u{1F6C5}'),
+    ('\u{1F6CB}', '\u{1F6D2}'), ('\u{1F6D5}', '\u{1F6E5}'), ('\u{1F6E9}', '\u{1F6E9}'), ('\u{1F6EB}', '\u{1F6F0}'),
+    ('\u{1F6F3}', '\u{1F6FF}'), ('\u{1F7DA}', '\u{1F7FF}'), ('\u{1F80C}', '\u{1F80F}'), ('\u{1F848}', '\u{1F84F}'),
+    ('\u{1F85A}', '\u{1F85F}'), ('\u{1F888}', '\u{1F88F}'), ('\u{1F8AE}', '\u{1F8AF}'), ('\u{1F8BC}', '\u{1F8BF}'),
+    ('\u{1F8C2}', '\u{1F8CF}'), ('\u{1F8D9}', '\u{1F8FF}'), ('\u{1F90C}', '\u{1F93A}'), ('\u{1F93C}', '\u{1F945}'),
+    ('\u{1F947}', '\u{1F9FF}'), ('\u{1FA58}', '\u{1FA5F}'), ('\u{1FA6E}', '\u{1FAFF}'), ('\u{1FC00}', '\u{1FFFD}'),
+];
+
+/**
+Does a code point have the Extended_Pictographic property?
+*/
+pub fn is_extended_pictographic(c: char) -> bool {
+    EXTENDED_PICTOGRAPHIC.binary_search_by(|&(lo, hi)| {
+        if hi < c {
+            ::core::cmp::Ordering::Less
+        } else if lo > c {
+            ::core::cmp::Ordering::Greater
+        } else {
+            ::core::cmp::Ordering::Equal
+        }
+    }).is_ok()
+}
@@ -0,0 +1,415 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Searching for grapheme clusters in strings.
+*/
+use bounds::is_gc_boundary;
+use grapheme::Gc;
+#[cfg(all(feature = "pattern", feature = "alloc"))] use grapheme::GcBuf;
+use span::Match;
+use StrCursor;
+
+/**
+An iterator over the byte offsets of the occurrences of a grapheme cluster in a string, returned from [`Gc::occurrences_in`](struct.Gc.html#method.occurrences_in).
+*/
+#[derive(Clone, Debug)]
+pub struct Occurrences<'g, 'h> {
+    needle: &'g str,
+    haystack: &'h str,
+    pos: usize,
+}
+
+impl<'g, 'h> Iterator for Occurrences<'g, 'h> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.pos < self.haystack.len() {
+            let at = match self.haystack[self.pos..].find(self.needle) {
+                Some(offset) => self.pos + offset,
+                None => {
+                    self.pos = self.haystack.len();
+                    return None;
+                }
+            };
+            let end = at + self.needle.len();
+            if is_gc_boundary(self.haystack, at) && is_gc_boundary(self.haystack, end) {
+                self.pos = end;
+                return Some(at);
+            }
+            // Not a whole cluster; resume from the next code point, since a valid match may overlap this one.
+            self.pos = at + self.haystack[at..].chars().next().map_or(1, char::len_utf8);
+        }
+        None
+    }
+}
+
+impl Gc {
+    /**
+    Returns the byte offset of the first occurrence of this grapheme cluster in `haystack`, or `None` if there is none.
+
+    Unlike `str::find`, only occurrences which are whole grapheme clusters of `haystack` are found.  For example, `"e"` is *not* found in `"e\u{301}"`, as the `"e"` there is only part of a cluster.
+    */
+    pub fn find_in(&self, haystack: &str) -> Option<usize> {
+        self.occurrences_in(haystack).next()
+    }
+
+    /**
+    Returns an iterator over the byte offsets of every non-overlapping occurrence of this grapheme cluster in `haystack`.
+
+    As with [`find_in`](#method.find_in), only occurrences which are whole grapheme clusters of `haystack` are found.
+    */
+    pub fn occurrences_in<'g, 'h>(&'g self, haystack: &'h str) -> Occurrences<'g, 'h> {
+        Occurrences {
+            needle: self.as_str(),
+            haystack,
+            pos: 0,
+        }
+    }
+}
+
+/**
+An iterator over at most `n` fields of the string after a cursor, separated by a grapheme cluster, returned from [`StrCursor::splitn_after`](struct.StrCursor.html#method.splitn_after).
+*/
+#[derive(Clone, Debug)]
+pub struct SplitNAfter<'a, 'g> {
+    occurrences: Occurrences<'g, 'a>,
+    rest: StrCursor<'a>,
+    n: usize,
+}
+
+impl<'a, 'g> SplitNAfter<'a, 'g> {
+    /**
+    Returns a cursor at the start of the text which has not yet been yielded as a field.
+
+    Once the iterator has yielded its last field, this is at the end of the string.  Calling this after taking fewer than `n` fields allows parsing to continue from wherever splitting stopped.
+    */
+    pub fn remainder_cursor(&self) -> StrCursor<'a> {
+        self.rest
+    }
+}
+
+impl<'a, 'g> Iterator for SplitNAfter<'a, 'g> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        let s = self.rest.s;
+        let start = self.rest.at;
+        let end = match self.n {
+            0 => None,
+            _ => self.occurrences.next(),
+        };
+        match end {
+            Some(end) => {
+                self.rest.at = end + self.occurrences.needle.len();
+                Some(&s[start..end])
+            },
+            None => {
+                self.n = 0;
+                self.rest.at = s.len();
+                Some(&s[start..])
+            }
+        }
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns an iterator over the fields of the string after the cursor, separated by `sep`, returning at most `n` fields.
+
+    Field boundaries are the same as for `str::splitn`, except that only occurrences of `sep` which are whole grapheme clusters are treated as separators (see [`Gc::find_in`](struct.Gc.html#method.find_in)).  The last field contains the remainder of the string, and if `n` is zero, no fields are produced.
+
+    Use [`SplitNAfter::remainder_cursor`](struct.SplitNAfter.html#method.remainder_cursor) to resume parsing after the fields which have been taken.
+    */
+    pub fn splitn_after<'g>(self, n: usize, sep: &'g Gc) -> SplitNAfter<'a, 'g> {
+        SplitNAfter {
+            occurrences: Occurrences {
+                needle: sep.as_str(),
+                haystack: self.s,
+                pos: self.at,
+            },
+            rest: self,
+            n,
+        }
+    }
+
+    /**
+    Returns the first occurrence of `gc` to the right of the cursor, or `None` if there is none.
+
+    As with [`Gc::find_in`](struct.Gc.html#method.find_in), only occurrences which are whole grapheme clusters are found.
+    */
+    pub fn find_after_match(self, gc: &Gc) -> Option<Match<'a>> {
+        let at = Occurrences {
+            needle: gc.as_str(),
+            haystack: self.s,
+            pos: self.at,
+        }.next()?;
+        let mut start = self;
+        let mut end = self;
+        start.at = at;
+        end.at = at + gc.len();
+        Some(Match::between(start, end))
+    }
+}
+
+/**
+An iterator over the occurrences of a grapheme cluster before a cursor, from right to left, returned from [`StrCursor::rmatches_gc`](struct.StrCursor.html#method.rmatches_gc).
+*/
+#[derive(Clone, Debug)]
+pub struct RMatchesGc<'a, 'g> {
+    needle: &'g str,
+    cur: StrCursor<'a>,
+}
+
+impl<'a, 'g> Iterator for RMatchesGc<'a, 'g> {
+    type Item = (usize, StrCursor<'a>);
+
+    fn next(&mut self) -> Option<(usize, StrCursor<'a>)> {
+        let s = self.cur.s;
+        let mut pos = self.cur.at;
+        while let Some(at) = s[..pos].rfind(self.needle) {
+            let end = at + self.needle.len();
+            if is_gc_boundary(s, at) && is_gc_boundary(s, end) {
+                self.cur.at = at;
+                return Some((at, self.cur));
+            }
+            // Not a whole cluster; a valid match may still overlap this one, so drop only its last code point.
+            pos = end - self.needle.chars().next_back().map_or(1, char::len_utf8);
+        }
+        self.cur.at = 0;
+        None
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns an iterator over every non-overlapping occurrence of `needle` before the cursor, from right to left.
+
+    Each item is the byte offset of an occurrence, along with a cursor positioned at its start.  As with [`Gc::find_in`](struct.Gc.html#method.find_in), only occurrences which are whole grapheme clusters are found.  This is the reverse complement of [`find_after_match`](#method.find_after_match), and is intended for "find previous" style searching.
+    */
+    pub fn rmatches_gc<'g>(self, needle: &'g Gc) -> RMatchesGc<'a, 'g> {
+        RMatchesGc {
+            needle: needle.as_str(),
+            cur: self,
+        }
+    }
+}
+
+/**
+A searcher for a grapheme cluster pattern.
+
+Requires the `pattern` feature.
+*/
+#[cfg(feature = "pattern")]
+#[derive(Clone, Debug)]
+pub struct GcSearcher<'h, 'g> {
+    occurrences: Occurrences<'g, 'h>,
+    pos: usize,
+    next_match: Option<usize>,
+}
+
+#[cfg(feature = "pattern")]
+unsafe impl<'h, 'g> ::core::str::pattern::Searcher<'h> for GcSearcher<'h, 'g> {
+    fn haystack(&self) -> &'h str {
+        self.occurrences.haystack
+    }
+
+    fn next(&mut self) -> ::core::str::pattern::SearchStep {
+        use core::str::pattern::SearchStep;
+        let len = self.occurrences.haystack.len();
+        if self.pos == len {
+            return SearchStep::Done;
+        }
+        let at = match self.next_match {
+            Some(at) => at,
+            None => self.occurrences.next().unwrap_or(len)
+        };
+        if at > self.pos {
+            // Reject everything up to the next match, then return to it.
+            let start = self.pos;
+            self.pos = at;
+            self.next_match = if at < len { Some(at) } else { None };
+            SearchStep::Reject(start, at)
+        } else {
+            let end = at + self.occurrences.needle.len();
+            self.pos = end;
+            self.next_match = None;
+            SearchStep::Match(at, end)
+        }
+    }
+}
+
+/**
+Allows a grapheme cluster to be used as a pattern with `str` methods such as `find` and `split`.
+
+Only occurrences which are whole grapheme clusters of the haystack are matched; see [`Gc::find_in`](struct.Gc.html#method.find_in).
+
+Requires the `pattern` feature, and a nightly compiler.
+*/
+#[cfg(feature = "pattern")]
+impl<'g> ::core::str::pattern::Pattern for &'g Gc {
+    type Searcher<'h> = GcSearcher<'h, 'g>;
+
+    fn into_searcher(self, haystack: &str) -> GcSearcher<'_, 'g> {
+        GcSearcher {
+            occurrences: self.occurrences_in(haystack),
+            pos: 0,
+            next_match: None,
+        }
+    }
+}
+
+/**
+Allows a grapheme cluster to be used as a pattern with `str` methods such as `find` and `split`.
+
+See the implementation for `&Gc`.
+
+Requires the `pattern` and `alloc` features, and a nightly compiler.
+*/
+#[cfg(all(feature = "pattern", feature = "alloc"))]
+impl<'g> ::core::str::pattern::Pattern for &'g GcBuf {
+    type Searcher<'h> = GcSearcher<'h, 'g>;
+
+    fn into_searcher(self, haystack: &str) -> GcSearcher<'_, 'g> {
+        self.as_gc().into_searcher(haystack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use grapheme::Gc;
+    use StrCursor;
+
+    fn gc(s: &str) -> &Gc {
+        Gc::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_find_in() {
+        let s = "cafe\u{301} fe\u{301}te fete";
+        assert_eq!(s.find("e"), Some(3));
+        assert_eq!(gc("e").find_in(s), Some(12));
+        assert_eq!(gc("e\u{301}").find_in(s), Some(3));
+        assert_eq!(gc("t").find_in(s), Some(11));
+        assert_eq!(gc("x").find_in(s), None);
+        assert_eq!(gc("e").find_in(""), None);
+
+        // A regional indicator pair only matches on a pair boundary.
+        let flags = "\u{1F1E6}\u{1F1E6}\u{1F1FA}\u{1F1E6}";
+        assert_eq!(flags.find("\u{1F1E6}\u{1F1FA}"), Some(4));
+        assert_eq!(gc("\u{1F1E6}\u{1F1FA}").find_in(flags), None);
+        assert_eq!(gc("\u{1F1FA}\u{1F1E6}").find_in(flags), Some(8));
+    }
+
+    #[test]
+    fn test_occurrences_in() {
+        let s = "e\u{301}e e\u{300}ee";
+        assert_eq!(s.match_indices("e").map(|(i, _)| i).collect::<Vec<_>>(), vec![0, 3, 5, 8, 9]);
+        assert_eq!(gc("e").occurrences_in(s).collect::<Vec<_>>(), vec![3, 8, 9]);
+        assert_eq!(gc("e\u{301}").occurrences_in(s).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(gc(" ").occurrences_in(s).collect::<Vec<_>>(), vec![4]);
+    }
+
+    #[test]
+    fn test_splitn_after() {
+        let s = "key: value: with: colons";
+        let cur = StrCursor::new_at_start(s);
+        for n in 0..6 {
+            assert_eq!(cur.splitn_after(n, gc(":")).collect::<Vec<_>>(),
+                s.splitn(n, ':').collect::<Vec<_>>(), "n = {}", n);
+        }
+
+        let mut fields = cur.splitn_after(2, gc(":"));
+        assert_eq!(fields.next(), Some("key"));
+        let rest = fields.remainder_cursor();
+        assert_eq!(rest.slice_after(), " value: with: colons");
+        assert_eq!(fields.next(), Some(" value: with: colons"));
+        assert_eq!(fields.next(), None);
+        assert_eq!(fields.remainder_cursor(), StrCursor::new_at_end(s));
+
+        // Continue parsing from the remainder.
+        let mut fields = rest.splitn_after(2, gc(":"));
+        assert_eq!(fields.next(), Some(" value"));
+        assert_eq!(fields.remainder_cursor().slice_after(), " with: colons");
+
+        // Starting mid-string, with a separator that is part of a larger cluster.
+        let s = "a,e\u{301},b,c";
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 2);
+        assert_eq!(cur.splitn_after(3, gc(",")).collect::<Vec<_>>(), vec!["e\u{301}", "b", "c"]);
+        assert_eq!(cur.splitn_after(9, gc("e")).collect::<Vec<_>>(), vec!["e\u{301},b,c"]);
+        assert_eq!(StrCursor::new_at_end(s).splitn_after(2, gc(",")).collect::<Vec<_>>(), vec![""]);
+    }
+
+    #[test]
+    fn test_find_after_match() {
+        let s = "cafe\u{301} fe\u{301}te fete";
+        let cur = StrCursor::new_at_start(s);
+        let m = cur.find_after_match(gc("e\u{301}")).unwrap();
+        assert_eq!(m.start().slice_between(m.end()), Some(m.as_str()));
+        assert_eq!(&*m, "e\u{301}");
+        assert_eq!(m.start().byte_pos(), 3);
+        let m = m.end().find_after_match(gc("e\u{301}")).unwrap();
+        assert_eq!(m.start().slice_between(m.end()), Some(m.as_str()));
+        assert_eq!(m.start().byte_pos(), 8);
+        assert_eq!(m.end().find_after_match(gc("e\u{301}")), None);
+
+        let m = cur.find_after_match(gc("e")).unwrap();
+        assert_eq!(m.start().byte_pos(), 12);
+        assert_eq!(m.end().slice_after(), " fete");
+        assert_eq!(StrCursor::new_at_end(s).find_after_match(gc("e")), None);
+    }
+
+    #[test]
+    fn test_rmatches_gc() {
+        fn rmatches(cur: StrCursor<'_>, needle: &str) -> Vec<usize> {
+            cur.rmatches_gc(gc(needle)).map(|(at, cur)| {
+                assert_eq!(cur.byte_pos(), at);
+                assert!(cur.slice_after().starts_with(needle));
+                at
+            }).collect()
+        }
+
+        let s = "e\u{301}e e\u{300}ee";
+        let end = StrCursor::new_at_end(s);
+        let mut forward = gc("e").occurrences_in(s).collect::<Vec<_>>();
+        forward.reverse();
+        assert_eq!(rmatches(end, "e"), forward);
+        assert_eq!(rmatches(end, "e"), vec![9, 8, 3]);
+        assert_eq!(rmatches(end, "e\u{301}"), vec![0]);
+        assert_eq!(rmatches(end, "x"), Vec::<usize>::new());
+        assert_eq!(rmatches(StrCursor::new_at_left_of_byte_pos(s, 9), "e"), vec![8, 3]);
+        assert_eq!(rmatches(StrCursor::new_at_left_of_byte_pos(s, 3), "e"), Vec::<usize>::new());
+
+        // A partial match overlapping a whole one.
+        let flags = "\u{1F1E6}\u{1F1FA}\u{1F1FA}\u{1F1E6}";
+        assert_eq!(rmatches(StrCursor::new_at_end(flags), "\u{1F1FA}\u{1F1E6}"), vec![8]);
+        assert_eq!(rmatches(StrCursor::new_at_end(flags), "\u{1F1E6}\u{1F1FA}"), vec![0]);
+    }
+
+    #[cfg(feature = "pattern")]
+    #[test]
+    fn test_pattern() {
+        let s = "cafe\u{301} fe\u{301}te fete";
+        assert_eq!(s.find(gc("e")), Some(12));
+        #[cfg(feature = "alloc")]
+        assert_eq!(s.find(&::grapheme::GcBuf::from('e')), Some(12));
+        assert_eq!(s.find(gc("e\u{301}")), Some(3));
+        assert_eq!(s.split(gc("e")).collect::<Vec<_>>(), vec!["cafe\u{301} fe\u{301}t", " f", "t", ""]);
+        assert_eq!(s.split(gc(" ")).collect::<Vec<_>>(), vec!["cafe\u{301}", "fe\u{301}te", "fete"]);
+        assert_eq!(s.matches(gc("e\u{301}")).count(), 2);
+        assert!(!"e\u{301}".contains(gc("e")));
+        assert_eq!("x".split(gc("e")).collect::<Vec<_>>(), vec!["x"]);
+        assert_eq!("".split(gc("e")).collect::<Vec<_>>(), vec![""]);
+    }
+}
@@ -0,0 +1,165 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Serde support for grapheme clusters.
+
+Clusters are serialized as strings.  Deserialization checks that the string is exactly one grapheme cluster.
+*/
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use core::fmt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{Error, Unexpected, Visitor};
+use grapheme::{Gc, GcBuf};
+use CursorPos;
+
+const EXPECTING: &str = "a single grapheme cluster";
+
+impl Serialize for Gc {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl Serialize for GcBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+struct GcBufVisitor;
+
+impl<'de> Visitor<'de> for GcBufVisitor {
+    type Value = GcBuf;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(EXPECTING)
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<GcBuf, E>
+    where E: Error {
+        Ok(GcBuf::from(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<GcBuf, E>
+    where E: Error {
+        match Gc::from_str(v) {
+            Some(gc) => Ok(gc.to_owned()),
+            None => Err(E::invalid_value(Unexpected::Str(v), &self)),
+        }
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<GcBuf, E>
+    where E: Error {
+        use core::convert::TryFrom;
+        GcBuf::try_from(v)
+            .map_err(|e| E::invalid_value(Unexpected::Str(e.as_str()), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for GcBuf {
+    fn deserialize<D>(deserializer: D) -> Result<GcBuf, D::Error>
+    where D: Deserializer<'de> {
+        deserializer.deserialize_string(GcBufVisitor)
+    }
+}
+
+struct GcVisitor;
+
+impl<'de> Visitor<'de> for GcVisitor {
+    type Value = &'de Gc;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{} borrowed from the input", EXPECTING)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<&'de Gc, E>
+    where E: Error {
+        Gc::from_str(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+/**
+Deserializes a borrowed grapheme cluster without copying.
+
+This only succeeds if the deserializer can borrow the string from its input; for example, a JSON string containing escape sequences cannot be borrowed.  Use `GcBuf` where this is a problem.
+*/
+impl<'de: 'a, 'a> Deserialize<'de> for &'a Gc {
+    fn deserialize<D>(deserializer: D) -> Result<&'a Gc, D::Error>
+    where D: Deserializer<'de> {
+        deserializer.deserialize_str(GcVisitor)
+    }
+}
+
+/**
+Serializes the handle as its index.
+
+A `CursorPos` is only meaningful together with the [`PositionSet`](struct.PositionSet.html) that issued it.
+*/
+impl Serialize for CursorPos {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        serializer.serialize_u64(self.0 as u64)
+    }
+}
+
+impl<'de> Deserialize<'de> for CursorPos {
+    fn deserialize<D>(deserializer: D) -> Result<CursorPos, D::Error>
+    where D: Deserializer<'de> {
+        usize::deserialize(deserializer).map(CursorPos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::ToOwned;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use serde_json;
+    use grapheme::{Gc, GcBuf};
+    use {CursorPos, PositionSet, StrCursorBuf};
+
+    #[test]
+    fn test_round_trip() {
+        let g = Gc::from_str("e\u{301}").unwrap();
+        let json = serde_json::to_string(g).unwrap();
+        assert_eq!(json, "\"e\u{301}\"");
+        assert_eq!(serde_json::to_string(&g.to_owned()).unwrap(), json);
+        assert_eq!(serde_json::from_str::<GcBuf>(&json).unwrap(), g);
+        assert_eq!(serde_json::from_str::<&Gc>(&json).unwrap(), g);
+
+        let tokens: Vec<GcBuf> = serde_json::from_str("[\"a\", \"\\u00e9\", \"\u{1F1E6}\u{1F1FA}\"]").unwrap();
+        assert_eq!(tokens, vec!["a", "\u{E9}", "\u{1F1E6}\u{1F1FA}"]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let err = serde_json::from_str::<GcBuf>("\"ab\"").unwrap_err().to_string();
+        assert!(err.contains("\"ab\""), "{}", err);
+        assert!(err.contains("a single grapheme cluster"), "{}", err);
+        assert!(serde_json::from_str::<GcBuf>("\"\"").is_err());
+        assert!(serde_json::from_str::<GcBuf>("\"a\\u0301b\"").is_err());
+        assert!(serde_json::from_str::<&Gc>("\"ab\"").is_err());
+        // Escapes force a copy, so cannot be borrowed.
+        assert!(serde_json::from_str::<&Gc>("\"\\u00e9\"").is_err());
+    }
+
+    #[test]
+    fn test_cursor_pos() {
+        let mut set = PositionSet::new(StrCursorBuf::new_at_start(String::from("abc")));
+        let pos = set.track(2);
+        let json = serde_json::to_string(&pos).unwrap();
+        let back: CursorPos = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, pos);
+        assert_eq!(set.get(back).byte_pos(), 2);
+    }
+}
@@ -0,0 +1,459 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Defines a type for representing the region between two cursors.
+*/
+use core::ops::{Deref, Range};
+use grapheme::Gc;
+use uniseg::UnicodeSegmentation as UniSeg;
+use StrCursor;
+
+/**
+A region of a string, delimited by two cursors into the same string.
+
+The start of a span is never to the right of its end.  Like `StrCursor`, two spans are only equal if they refer to the *same* string slice; spans over equal contents in different strings are not equal.
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Span<'a> {
+    start: StrCursor<'a>,
+    end: StrCursor<'a>,
+}
+
+impl<'a> Span<'a> {
+    /**
+    Create a new span between two cursors.
+
+    The cursors may be given in either order.  Returns `None` if the cursors are from different strings (even different subsets of the same string).
+    */
+    pub fn new(a: StrCursor<'a>, b: StrCursor<'a>) -> Option<Span<'a>> {
+        if !::str_eq_literal(a.s, b.s) {
+            return None;
+        }
+        if a.at <= b.at {
+            Some(Span { start: a, end: b })
+        } else {
+            Some(Span { start: b, end: a })
+        }
+    }
+
+    /**
+    Returns a cursor at the start of the span.
+    */
+    pub fn start(&self) -> StrCursor<'a> {
+        self.start
+    }
+
+    /**
+    Returns a cursor at the end of the span.
+    */
+    pub fn end(&self) -> StrCursor<'a> {
+        self.end
+    }
+
+    /**
+    Returns the contents of the span.
+    */
+    pub fn as_str(&self) -> &'a str {
+        &self.start.s[self.byte_range()]
+    }
+
+    /**
+    Returns the span's position within the string as a range of byte offsets.
+    */
+    pub fn byte_range(&self) -> Range<usize> {
+        self.start.byte_pos()..self.end.byte_pos()
+    }
+
+    /**
+    Returns the length of the span in bytes.
+    */
+    pub fn len_bytes(&self) -> usize {
+        self.end.byte_pos() - self.start.byte_pos()
+    }
+
+    /**
+    Is this span empty?
+    */
+    pub fn is_empty(&self) -> bool {
+        self.start.at == self.end.at
+    }
+
+    /**
+    Does this span contain the given cursor position?
+
+    Both the start and end positions of the span are considered to be contained within it.  Cursors from a different string are never contained.
+    */
+    pub fn contains(&self, cur: StrCursor<'a>) -> bool {
+        ::str_eq_literal(self.start.s, cur.s)
+            && self.start.at <= cur.at && cur.at <= self.end.at
+    }
+
+    /**
+    Does this span entirely contain the `other` span?
+    */
+    pub fn contains_span(&self, other: Span<'a>) -> bool {
+        self.contains(other.start) && self.contains(other.end)
+    }
+
+    /**
+    Returns the region common to both spans.
+
+    Returns `None` if the spans are from different strings, or do not overlap.  Spans which merely touch produce an empty span at the point where they meet.
+    */
+    pub fn intersect(&self, other: Span<'a>) -> Option<Span<'a>> {
+        if !::str_eq_literal(self.start.s, other.start.s) {
+            return None;
+        }
+        let start = if self.start.at >= other.start.at { self.start } else { other.start };
+        let end = if self.end.at <= other.end.at { self.end } else { other.end };
+        if start.at <= end.at {
+            Some(Span { start, end })
+        } else {
+            None
+        }
+    }
+
+    /**
+    Returns the smallest span which contains both spans, including any gap between them.
+
+    Returns `None` if the spans are from different strings.
+    */
+    pub fn join(&self, other: Span<'a>) -> Option<Span<'a>> {
+        if !::str_eq_literal(self.start.s, other.start.s) {
+            return None;
+        }
+        let start = if self.start.at <= other.start.at { self.start } else { other.start };
+        let end = if self.end.at >= other.end.at { self.end } else { other.end };
+        Some(Span { start, end })
+    }
+
+    /**
+    An iterator over the grapheme clusters in the span.
+    */
+    pub fn iter(&self) -> SpanIter<'a> {
+        SpanIter(self.as_str())
+    }
+
+    /**
+    An iterator over the code points in the span.
+    */
+    pub fn iter_cp(&self) -> ::core::str::Chars<'a> {
+        self.as_str().chars()
+    }
+}
+
+/**
+An iterator over the grapheme clusters in a [`Span`](struct.Span.html).
+*/
+#[derive(Clone, Debug)]
+pub struct SpanIter<'a>(&'a str);
+
+impl<'a> Iterator for SpanIter<'a> {
+    type Item = &'a Gc;
+
+    fn next(&mut self) -> Option<&'a Gc> {
+        let (gc, tail) = Gc::split_from(self.0)?;
+        self.0 = tail;
+        Some(gc)
+    }
+}
+
+impl<'a> DoubleEndedIterator for SpanIter<'a> {
+    fn next_back(&mut self) -> Option<&'a Gc> {
+        let gr = UniSeg::graphemes(self.0, /*is_extended:*/true).next_back()?;
+        self.0 = &self.0[..self.0.len() - gr.len()];
+        Some(unsafe { Gc::from_str_unchecked(gr) })
+    }
+}
+
+/**
+The text matched by a scanning or searching method, along with cursors at either end of it.
+
+A `Match` dereferences to the matched text, and can be converted into a [`Span`](struct.Span.html).
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Match<'a> {
+    text: &'a str,
+    start: StrCursor<'a>,
+    end: StrCursor<'a>,
+}
+
+impl<'a> Match<'a> {
+    pub(crate) fn between(start: StrCursor<'a>, end: StrCursor<'a>) -> Match<'a> {
+        debug_assert!(start.at <= end.at);
+        Match {
+            text: &start.s[start.at..end.at],
+            start,
+            end,
+        }
+    }
+
+    /**
+    Returns the matched text.
+    */
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /**
+    Returns a cursor at the start of the match.
+    */
+    pub fn start(&self) -> StrCursor<'a> {
+        self.start
+    }
+
+    /**
+    Returns a cursor at the end of the match.
+    */
+    pub fn end(&self) -> StrCursor<'a> {
+        self.end
+    }
+}
+
+impl<'a> Deref for Match<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text
+    }
+}
+
+impl<'a> From<Match<'a>> for Span<'a> {
+    fn from(m: Match<'a>) -> Span<'a> {
+        Span { start: m.start, end: m.end }
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns the span between this cursor and another.
+
+    Returns `None` if the cursors are from different strings (even different subsets of the same string).  See [`Span::new`](struct.Span.html#method.new).
+    */
+    pub fn span_to(self, other: StrCursor<'a>) -> Option<Span<'a>> {
+        Span::new(self, other)
+    }
+
+    /**
+    Returns the span covering up to `n` grapheme clusters either side of this cursor.
+
+    This is the span equivalent of [`surrounding`](#method.surrounding).
+    */
+    pub fn surrounding_span(&self, n: usize) -> Span<'a> {
+        let (start, end) = self.context_window(n);
+        Span { start, end }
+    }
+
+    /**
+    Returns the span between this cursor and another, with any leading and trailing whitespace grapheme clusters removed.
+
+    This combines [`skip_whitespace_after`](#method.skip_whitespace_after) and [`skip_whitespace_before`](#method.skip_whitespace_before), but never moves either end of the span past the other: if the region is entirely whitespace, the result is an empty span at its end.  As with `skip_whitespace_after`, a grapheme cluster is considered whitespace if *all* of its code points are whitespace.
+
+    Returns `None` if the cursors are from different strings.  The cursors may be given in either order.
+    */
+    pub fn trimmed_span(self, other: StrCursor<'a>) -> Option<Span<'a>> {
+        let Span { mut start, mut end } = Span::new(self, other)?;
+        while start.at < end.at {
+            match start.next() {
+                Some((gc, next)) if gc.is_whitespace() => start = next,
+                _ => break
+            }
+        }
+        while end.at > start.at {
+            match end.prev() {
+                Some((gc, prev)) if gc.is_whitespace() => end = prev,
+                _ => break
+            }
+        }
+        Some(Span { start, end })
+    }
+
+    /**
+    Returns the longest run of grapheme clusters immediately to the right of the cursor for which `pred` returns `true`.
+
+    The match may be empty, in which case both of its cursors are at this cursor's position.
+    */
+    pub fn after_while_match<P>(self, mut pred: P) -> Match<'a>
+    where P: FnMut(&Gc) -> bool {
+        let mut end = self;
+        while let Some((gc, next)) = end.next() {
+            if !pred(gc) {
+                break;
+            }
+            end = next;
+        }
+        Match::between(self, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use std::collections::HashSet;
+    use super::{Match, Span};
+    use StrCursor;
+
+    fn span(s: &str, beg: usize, end: usize) -> Span<'_> {
+        let beg = StrCursor::new_at_left_of_byte_pos(s, beg);
+        let end = StrCursor::new_at_left_of_byte_pos(s, end);
+        beg.span_to(end).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        let s = "noe\u{308}l 💪!";
+        let a = StrCursor::new_at_left_of_byte_pos(s, 2);
+        let b = StrCursor::new_at_left_of_byte_pos(s, 11);
+        let sp = Span::new(b, a).unwrap();
+        assert_eq!(sp, Span::new(a, b).unwrap());
+        assert_eq!((sp.start(), sp.end()), (a, b));
+        assert_eq!(sp.as_str(), "e\u{308}l 💪");
+        assert_eq!(sp.byte_range(), 2..11);
+        assert_eq!(sp.len_bytes(), 9);
+        assert!(!sp.is_empty());
+        assert!(a.span_to(a).unwrap().is_empty());
+        assert_eq!(sp.iter().map(|gc| gc.as_str()).collect::<Vec<_>>(),
+            vec!["e\u{308}", "l", " ", "💪"]);
+        assert_eq!(sp.iter().rev().map(|gc| gc.as_str()).collect::<Vec<_>>(),
+            vec!["💪", " ", "l", "e\u{308}"]);
+        assert_eq!(sp.iter_cp().collect::<Vec<_>>(),
+            vec!['e', '\u{308}', 'l', ' ', '💪']);
+        assert_eq!(Span::new(a, StrCursor::new_at_end(&s[1..])), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = "they hit, fight, kick";
+        let sp = span(s, 5, 15);
+        assert!(sp.contains(StrCursor::new_at_left_of_byte_pos(s, 5)));
+        assert!(sp.contains(StrCursor::new_at_left_of_byte_pos(s, 10)));
+        assert!(sp.contains(StrCursor::new_at_left_of_byte_pos(s, 15)));
+        assert!(!sp.contains(StrCursor::new_at_left_of_byte_pos(s, 4)));
+        assert!(!sp.contains(StrCursor::new_at_left_of_byte_pos(s, 16)));
+        assert!(!sp.contains(StrCursor::new_at_left_of_byte_pos(&s[..20], 10)));
+        assert!(sp.contains_span(span(s, 6, 15)));
+        assert!(sp.contains_span(sp));
+        assert!(!sp.contains_span(span(s, 4, 10)));
+    }
+
+    #[test]
+    fn test_intersect_and_join() {
+        let s = "they hit, fight, kick, wreak havoc, and rejoice";
+        let a = span(s, 5, 15);
+
+        // Overlapping.
+        let b = span(s, 10, 21);
+        assert_eq!(a.intersect(b).map(|sp| sp.as_str()), Some("fight"));
+        assert_eq!(b.intersect(a), a.intersect(b));
+        assert_eq!(a.join(b).map(|sp| sp.as_str()), Some("hit, fight, kick"));
+        assert_eq!(b.join(a), a.join(b));
+
+        // Nested.
+        let c = span(s, 6, 8);
+        assert_eq!(a.intersect(c), Some(c));
+        assert_eq!(c.intersect(a), Some(c));
+        assert_eq!(a.join(c), Some(a));
+
+        // Touching.
+        let d = span(s, 15, 21);
+        assert_eq!(a.intersect(d), Some(span(s, 15, 15)));
+
+        // Disjoint.
+        let e = span(s, 23, 34);
+        assert_eq!(a.intersect(e), None);
+        assert_eq!(e.intersect(a), None);
+        assert_eq!(a.join(e).map(|sp| sp.as_str()), Some("hit, fight, kick, wreak havoc"));
+
+        // Different strings.
+        let f = span(&s[1..], 5, 15);
+        assert_eq!(a.intersect(f), None);
+        assert_eq!(a.join(f), None);
+    }
+
+    #[test]
+    fn test_surrounding_span() {
+        let s = "x\u{1F468}\u{200D}\u{1F469}yz";
+        let cur = StrCursor::new_at_left_of_byte_pos(s, 12);
+        let sp = cur.surrounding_span(1);
+        assert_eq!(sp.as_str(), "\u{1F468}\u{200D}\u{1F469}y");
+        let (before, after) = cur.surrounding(1);
+        assert_eq!(sp.len_bytes(), before.len() + after.len());
+        assert!(sp.contains(cur));
+        assert_eq!(StrCursor::new_at_start(s).surrounding_span(1).as_str(), "x");
+        assert!(cur.surrounding_span(0).is_empty());
+    }
+
+    #[test]
+    fn test_trimmed_span() {
+        let s = "key:\u{a0} value\u{a0}\u{a0}; \u{a0} \u{362}x";
+        let start = StrCursor::new_at_left_of_byte_pos(s, 4);
+        let end = StrCursor::new_at_left_of_byte_pos(s, 16);
+        let sp = start.trimmed_span(end).unwrap();
+        assert_eq!(sp.as_str(), "value");
+        assert_eq!(sp.byte_range(), 7..12);
+        assert_eq!(end.trimmed_span(start), Some(sp));
+
+        // A space carrying a combining mark is not whitespace.
+        let sp = StrCursor::new_at_left_of_byte_pos(s, 18).trimmed_span(StrCursor::new_at_end(s)).unwrap();
+        assert_eq!(sp.as_str(), " \u{362}x");
+
+        // Entirely whitespace.
+        let s = " \u{a0}\r\n\t";
+        let sp = StrCursor::new_at_start(s).trimmed_span(StrCursor::new_at_end(s)).unwrap();
+        assert!(sp.is_empty());
+        assert_eq!(sp.start(), StrCursor::new_at_end(s));
+        let sp = StrCursor::new_at_end(&s[1..]).trimmed_span(StrCursor::new_at_end(&s[1..]));
+        assert_eq!(sp.map(|sp| sp.is_empty()), Some(true));
+
+        assert_eq!(StrCursor::new_at_start(s).trimmed_span(StrCursor::new_at_end(&s[1..])), None);
+    }
+
+    fn check_match(m: Match<'_>) {
+        assert_eq!(m.start().slice_between(m.end()), Some(m.as_str()));
+        assert_eq!(m.start().byte_pos() + m.len(), m.end().byte_pos());
+        assert_eq!(Span::from(m).as_str(), &*m);
+    }
+
+    #[test]
+    fn test_after_while_match() {
+        let s = "e\u{301}e\u{301}x12ab";
+        let cur = StrCursor::new_at_start(s);
+        let m = cur.after_while_match(|gc| gc.base_char() == 'e');
+        check_match(m);
+        assert_eq!(&*m, "e\u{301}e\u{301}");
+        assert_eq!(m.start(), cur);
+        assert_eq!(m.end().slice_after(), "x12ab");
+
+        let m = m.end().next().unwrap().1.after_while_match(|gc| gc.base_char().is_ascii_digit());
+        check_match(m);
+        assert_eq!(m.as_str(), "12");
+        assert!(m.starts_with('1'));
+
+        let m = cur.after_while_match(|_| false);
+        check_match(m);
+        assert!(m.is_empty());
+        assert_eq!((m.start(), m.end()), (cur, cur));
+
+        let m = cur.after_while_match(|_| true);
+        check_match(m);
+        assert_eq!(m.end(), StrCursor::new_at_end(s));
+    }
+
+    #[test]
+    fn test_hash() {
+        let s = "abcdef";
+        let mut set = HashSet::new();
+        set.insert(span(s, 1, 3));
+        set.insert(span(s, 3, 1));
+        set.insert(span(s, 1, 4));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&span(s, 1, 3)));
+    }
+}
@@ -0,0 +1,157 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Property testing strategies for grapheme clusters and cursors.
+
+Requires the `proptest` feature.
+*/
+use core::convert::TryFrom;
+use proptest::arbitrary::Arbitrary;
+use proptest::char;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+use grapheme::GcBuf;
+use uniseg::UnicodeSegmentation as UniSeg;
+use StrCursor;
+
+/**
+Generates grapheme clusters drawn from a spread of scripts and structures: ASCII, arbitrary single code points, CJK ideographs, Latin letters with combining marks, Hangul syllables built from conjoining jamo, Devanagari with vowel signs, emoji with skin tone modifiers, ZWJ emoji sequences, flags, and `"\r\n"`.
+*/
+impl Arbitrary for GcBuf {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<GcBuf>;
+
+    fn arbitrary_with(_: ()) -> BoxedStrategy<GcBuf> {
+        let marks = || vec(char::range('\u{300}', '\u{36F}'), 0..4);
+        let emoji = || char::range('\u{1F466}', '\u{1F469}');
+        prop_oneof![
+            char::range(' ', '~').prop_map(String::from),
+            any::<char>().prop_map(String::from),
+            char::range('\u{4E00}', '\u{9FFF}').prop_map(String::from),
+            (char::range('a', 'z'), marks()).prop_map(|(base, marks)| {
+                Some(base).into_iter().chain(marks).collect()
+            }),
+            (char::range('\u{1100}', '\u{1112}'), char::range('\u{1161}', '\u{1175}'),
+                prop::option::of(char::range('\u{11A8}', '\u{11C2}')))
+                .prop_map(|(l, v, t)| [l, v].iter().cloned().chain(t).collect()),
+            (char::range('\u{915}', '\u{939}'), prop::option::of(char::range('\u{93E}', '\u{94C}')))
+                .prop_map(|(c, v)| Some(c).into_iter().chain(v).collect()),
+            (emoji(), prop::option::of(char::range('\u{1F3FB}', '\u{1F3FF}')))
+                .prop_map(|(e, m)| Some(e).into_iter().chain(m).collect()),
+            vec(emoji(), 2..4).prop_map(|es| {
+                let parts: Vec<String> = es.into_iter().map(String::from).collect();
+                parts.join("\u{200D}")
+            }),
+            (char::range('\u{1F1E6}', '\u{1F1FF}'), char::range('\u{1F1E6}', '\u{1F1FF}'))
+                .prop_map(|(a, b)| [a, b].iter().cloned().collect()),
+            Just(String::from("\r\n")),
+        ]
+            .prop_filter_map("not a single grapheme cluster", |s: String| GcBuf::try_from(s).ok())
+            .boxed()
+    }
+}
+
+/**
+Generates cursors into `s`, positioned on grapheme cluster boundaries.
+
+The start and end of the string are always possible positions.
+*/
+pub fn cursor_in(s: &str) -> impl Strategy<Value=StrCursor<'_>> {
+    let mut bounds: Vec<usize> = UniSeg::grapheme_indices(s, /*is_extended:*/true)
+        .map(|(i, _)| i)
+        .collect();
+    bounds.push(s.len());
+    select(bounds).prop_map(move |pos| StrCursor::new_at_left_of_byte_pos(s, pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use bounds::is_gc_boundary;
+    use grapheme::{Gc, GcBuf};
+    use super::cursor_in;
+    use StrCursor;
+
+    proptest! {
+        #[test]
+        fn prop_gc_buf_is_one_cluster(g in any::<GcBuf>()) {
+            prop_assert!(Gc::from_str(g.as_str()).is_some());
+        }
+
+        #[test]
+        fn prop_slices_partition(s in ".*", seed in any::<prop::sample::Index>()) {
+            let cur = {
+                let mut bounds: Vec<usize> = (0..s.len() + 1)
+                    .filter(|&i| is_gc_boundary(&s, i))
+                    .collect();
+                let pos = bounds.swap_remove(seed.index(bounds.len()));
+                StrCursor::new_at_left_of_byte_pos(&s, pos)
+            };
+            prop_assert_eq!(cur.slice_before().len() + cur.slice_after().len(), cur.slice_all().len());
+            prop_assert_eq!(format!("{}{}", cur.slice_before(), cur.slice_after()), s.clone());
+        }
+
+        #[test]
+        fn prop_next_prev_round_trip(s in "\\PC{0,16}", seed in any::<prop::sample::Index>()) {
+            let cur = {
+                let mut bounds: Vec<usize> = (0..s.len() + 1)
+                    .filter(|&i| is_gc_boundary(&s, i))
+                    .collect();
+                let pos = bounds.swap_remove(seed.index(bounds.len()));
+                StrCursor::new_at_left_of_byte_pos(&s, pos)
+            };
+            if let Some(next) = cur.at_next() {
+                prop_assert_eq!(next.at_prev(), Some(cur));
+            }
+            if let Some(prev) = cur.at_prev() {
+                prop_assert_eq!(prev.at_next(), Some(cur));
+            }
+        }
+
+        #[test]
+        fn prop_hash_matches_eq(
+            s in "\\PC{0,16}",
+            seed_a in any::<prop::sample::Index>(),
+            seed_b in any::<prop::sample::Index>(),
+        ) {
+            use std::collections::hash_map::DefaultHasher;
+            use std::hash::{Hash, Hasher};
+            fn hash(cur: &StrCursor) -> u64 {
+                let mut h = DefaultHasher::new();
+                cur.hash(&mut h);
+                h.finish()
+            }
+            let s: &str = &s;
+            let bounds: Vec<usize> = (0..s.len() + 1)
+                .filter(|&i| is_gc_boundary(s, i))
+                .collect();
+            let a = StrCursor::new_at_left_of_byte_pos(s, bounds[seed_a.index(bounds.len())]);
+            let b = StrCursor::new_at_left_of_byte_pos(s, bounds[seed_b.index(bounds.len())]);
+            prop_assert_eq!(a == b, a.byte_pos() == b.byte_pos());
+            if a == b {
+                prop_assert_eq!(hash(&a), hash(&b));
+            }
+            let whole = StrCursor::new_at_left_of_byte_pos(&s[0..], a.byte_pos());
+            prop_assert_eq!(hash(&a), hash(&whole));
+        }
+    }
+
+    #[test]
+    fn test_cursor_in_on_boundary() {
+        let mut runner = proptest::test_runner::TestRunner::default();
+        for s in &["", "abc", "noe\u{308}l \u{1F4AA}!", "\u{1F468}\u{200D}\u{1F469}x\r\n\u{1F1E6}\u{1F1FA}"] {
+            runner.run(&cursor_in(s), |cur| {
+                prop_assert!(is_gc_boundary(s, cur.byte_pos()));
+                Ok(())
+            }).unwrap();
+        }
+    }
+}
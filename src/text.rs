@@ -0,0 +1,324 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Grapheme cluster-aware operations on whole strings.
+
+None of these functions will split a grapheme cluster.
+*/
+#[cfg(feature = "alloc")] use alloc::borrow::{Cow, ToOwned};
+#[cfg(feature = "alloc")] use alloc::string::String;
+use core::cmp::min;
+use uniseg::UnicodeSegmentation as UniSeg;
+use bounds::{is_gc_boundary, prev_gc_boundary};
+use grapheme::Gc;
+use StrCursor;
+
+/**
+Returns the longest prefix of `s` which is at most `max_bytes` long, and ends on a grapheme cluster boundary.
+*/
+pub fn truncate_to_bytes(s: &str, max_bytes: usize) -> &str {
+    &s[..prev_gc_boundary(s, min(max_bytes, s.len()))]
+}
+
+/**
+Returns the prefix of `s` containing at most `max_gcs` grapheme clusters.
+*/
+pub fn truncate_to_graphemes(s: &str, max_gcs: usize) -> &str {
+    match UniSeg::grapheme_indices(s, /*is_extended:*/true).nth(max_gcs) {
+        Some((end, _)) => &s[..end],
+        None => s
+    }
+}
+
+/**
+Truncates `s` to at most `max_gcs` grapheme clusters, ending with `ellipsis` if any truncation was necessary.
+
+The ellipsis counts toward the limit, so the result never contains more than `max_gcs` clusters; if the ellipsis is itself too long, it is truncated.  If `s` already fits, it is returned without allocating.
+
+Requires the `alloc` feature.
+*/
+#[cfg(feature = "alloc")]
+pub fn ellipsize<'a>(s: &'a str, max_gcs: usize, ellipsis: &str) -> Cow<'a, str> {
+    let kept = truncate_to_graphemes(s, max_gcs);
+    if kept.len() == s.len() {
+        return Cow::Borrowed(s);
+    }
+    let ellipsis_gcs = UniSeg::graphemes(ellipsis, /*is_extended:*/true).count();
+    if ellipsis_gcs >= max_gcs {
+        return Cow::Owned(truncate_to_graphemes(ellipsis, max_gcs).to_owned());
+    }
+    let kept = truncate_to_graphemes(kept, max_gcs - ellipsis_gcs);
+    Cow::Owned(format!("{}{}", kept, ellipsis))
+}
+
+/**
+Truncates `s` to at most `max_bytes` bytes, ending with `ellipsis` if any truncation was necessary.
+
+The ellipsis counts toward the limit, so the result is never longer than `max_bytes`; if the ellipsis is itself too long, it is truncated.  If `s` already fits, it is returned without allocating.
+
+Requires the `alloc` feature.
+*/
+#[cfg(feature = "alloc")]
+pub fn ellipsize_bytes<'a>(s: &'a str, max_bytes: usize, ellipsis: &str) -> Cow<'a, str> {
+    if s.len() <= max_bytes {
+        return Cow::Borrowed(s);
+    }
+    if ellipsis.len() >= max_bytes {
+        return Cow::Owned(truncate_to_bytes(ellipsis, max_bytes).to_owned());
+    }
+    let kept = truncate_to_bytes(s, max_bytes - ellipsis.len());
+    Cow::Owned(format!("{}{}", kept, ellipsis))
+}
+
+/**
+Does `haystack` start with `prefix`, ending on a grapheme cluster boundary of `haystack`?
+
+Unlike `str::starts_with`, this does not match if the prefix ends partway through a cluster.  For example, `"cafe\u{301}"` does *not* start with `"cafe"`, as the final `"e"` is part of the cluster `"e\u{301}"`.
+*/
+pub fn gc_starts_with(haystack: &str, prefix: &str) -> bool {
+    haystack.starts_with(prefix) && is_gc_boundary(haystack, prefix.len())
+}
+
+/**
+Does `haystack` end with `suffix`, starting on a grapheme cluster boundary of `haystack`?
+
+Unlike `str::ends_with`, this does not match if the suffix starts partway through a cluster.
+*/
+pub fn gc_ends_with(haystack: &str, suffix: &str) -> bool {
+    haystack.ends_with(suffix) && is_gc_boundary(haystack, haystack.len() - suffix.len())
+}
+
+/**
+Returns `haystack` with `prefix` removed, if it starts with `prefix` on a grapheme cluster boundary.
+
+See [`gc_starts_with`](fn.gc_starts_with.html).
+*/
+pub fn gc_strip_prefix<'a>(haystack: &'a str, prefix: &str) -> Option<&'a str> {
+    if gc_starts_with(haystack, prefix) {
+        Some(&haystack[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/**
+Are `a` and `b` equal, ignoring case, grapheme cluster by grapheme cluster?
+
+Each cluster of `a` is compared to the corresponding cluster of `b` using [`Gc::eq_ignore_case`](../struct.Gc.html#method.eq_ignore_case), and both strings must contain the same number of clusters.  This handles case mappings which change the number of code points *within* a cluster, so `"Stra\u{DF}e"` is equal to `"STRA\u{1E9E}E"`, but not to `"STRASSE"`, since `"\u{DF}"` and `"SS"` are a different number of clusters.  No allocation is performed, and if both strings are ASCII, a faster ASCII-only comparison is used.
+*/
+pub fn str_eq_ignore_case_graphemes(a: &str, b: &str) -> bool {
+    if a.is_ascii() && b.is_ascii() {
+        return a.eq_ignore_ascii_case(b);
+    }
+    let mut a = UniSeg::graphemes(a, /*is_extended:*/true);
+    let mut b = UniSeg::graphemes(b, /*is_extended:*/true);
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => unsafe {
+                if !Gc::from_str_unchecked(x).eq_ignore_case(Gc::from_str_unchecked(y)) {
+                    return false;
+                }
+            },
+            (None, None) => return true,
+            _ => return false
+        }
+    }
+}
+
+/**
+Reverses the order of the grapheme clusters in `s`.
+
+Unlike reversing the code points of a string, this keeps combining sequences, emoji and other multi-code point clusters intact.
+
+Requires the `alloc` feature.
+*/
+#[cfg(feature = "alloc")]
+pub fn reverse_graphemes(s: &str) -> String {
+    StrCursor::new_at_end(s).collect_before_reversed()
+}
+
+/**
+Returns the byte offset of the grapheme cluster displayed at column `target_col` of `s`.
+
+Columns are 0-based, and measured by summing [`Gc::width`](../struct.Gc.html#method.width) over each cluster, so `s` should be a single line.  Control characters, including tabs, have a width of zero.
+
+If `target_col` falls in the middle of a wide cluster, such as the second column of a double-width CJK ideograph or emoji, the offset of the *start* of that cluster is returned.  If there are zero-width clusters at `target_col`, the offset of the first of them is returned.  If `target_col` is at or past the total width of `s`, then `s.len()` is returned.
+
+The result is always a grapheme cluster boundary.
+
+Requires the `width` feature.
+*/
+#[cfg(feature = "width")]
+pub fn byte_at_width(s: &str, target_col: usize) -> usize {
+    let mut col = 0;
+    for (i, gr) in UniSeg::grapheme_indices(s, /*is_extended:*/true) {
+        let width = unsafe { Gc::from_str_unchecked(gr) }.width();
+        if target_col < col + width || target_col <= col {
+            return i;
+        }
+        col += width;
+    }
+    s.len()
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns the grapheme clusters to the left of the cursor, in reverse order.
+
+    See [`reverse_graphemes`](fn.reverse_graphemes.html).
+
+    Requires the `alloc` feature.
+    */
+    #[cfg(feature = "alloc")]
+    pub fn collect_before_reversed(&self) -> String {
+        let mut out = String::with_capacity(self.byte_pos());
+        for gc in self.iter_before() {
+            out.push_str(gc.as_str());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")] use super::{ellipsize, ellipsize_bytes, reverse_graphemes};
+    use super::{truncate_to_bytes, truncate_to_graphemes};
+
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+
+    #[test]
+    fn test_truncate_to_bytes() {
+        let s = format!("ab{}c", FAMILY);
+        assert_eq!(truncate_to_bytes(&s, 0), "");
+        assert_eq!(truncate_to_bytes(&s, 2), "ab");
+        for max in 3..2 + FAMILY.len() {
+            assert_eq!(truncate_to_bytes(&s, max), "ab");
+        }
+        assert_eq!(truncate_to_bytes(&s, 2 + FAMILY.len()), format!("ab{}", FAMILY));
+        assert_eq!(truncate_to_bytes(&s, 100), s);
+        assert_eq!(truncate_to_bytes("noe\u{308}l", 3), "no");
+    }
+
+    #[cfg(feature = "width")]
+    #[test]
+    fn test_byte_at_width() {
+        use std::vec::Vec;
+        use super::byte_at_width;
+        let s = "a漢b";
+        assert_eq!((0..6).map(|c| byte_at_width(s, c)).collect::<Vec<_>>(), vec![0, 1, 1, 4, 5, 5]);
+
+        let s = format!("x{}e\u{301}y", FAMILY);
+        let ends = [0, 1, 1, 1 + FAMILY.len(), 4 + FAMILY.len(), 5 + FAMILY.len()];
+        assert_eq!((0..6).map(|c| byte_at_width(&s, c)).collect::<Vec<_>>(), ends);
+
+        // Zero-width clusters at the target column are not skipped.
+        assert_eq!(byte_at_width("a\u{200B}b", 1), 1);
+        assert_eq!(byte_at_width("a\u{200B}b", 2), 5);
+        assert_eq!(byte_at_width("", 0), 0);
+        assert_eq!(byte_at_width("", 3), 0);
+    }
+
+    #[test]
+    fn test_str_eq_ignore_case_graphemes() {
+        use super::str_eq_ignore_case_graphemes as eq;
+        assert!(eq("If", "iF"));
+        assert!(eq("", ""));
+        assert!(!eq("if", "i"));
+        assert!(!eq("i", "if"));
+        assert!(eq("Stra\u{DF}e", "STRA\u{1E9E}E"));
+        assert!(!eq("Stra\u{DF}e", "STRASSE"));
+        assert!(eq("\u{130}stanbul", "i\u{307}STANBUL"));
+        assert!(!eq("\u{130}stanbul", "ISTANBUL"));
+        assert!(eq("Cafe\u{301}", "cAFE\u{301}"));
+        assert!(!eq("Cafe\u{301}", "cafe"));
+        assert!(!eq("cafe", "cafe\u{301}"));
+    }
+
+    #[test]
+    fn test_gc_starts_ends_with() {
+        use super::{gc_ends_with, gc_starts_with, gc_strip_prefix};
+        assert!("cafe\u{301}".starts_with("cafe"));
+        assert!(!gc_starts_with("cafe\u{301}", "cafe"));
+        assert!(gc_starts_with("cafe\u{301}", "caf"));
+        assert!(gc_starts_with("cafe\u{301}", "cafe\u{301}"));
+        assert!(gc_starts_with("cafe\u{301}", ""));
+        assert!(gc_starts_with("cafe", "cafe"));
+        assert!(!gc_starts_with("caf", "cafe"));
+        assert_eq!(gc_strip_prefix("cafe\u{301}s", "cafe\u{301}"), Some("s"));
+        assert_eq!(gc_strip_prefix("cafe\u{301}s", "cafe"), None);
+
+        assert!("cafe\u{301}".ends_with("\u{301}"));
+        assert!(!gc_ends_with("cafe\u{301}", "\u{301}"));
+        assert!(gc_ends_with("cafe\u{301}", "e\u{301}"));
+        assert!(gc_ends_with("cafe\u{301}", ""));
+
+        // "AU" then "AU": the second indicator of the first flag is not a boundary.
+        let flags = "\u{1F1E6}\u{1F1FA}\u{1F1E6}\u{1F1FA}";
+        assert!(flags.starts_with("\u{1F1E6}"));
+        assert!(!gc_starts_with(flags, "\u{1F1E6}"));
+        assert!(gc_starts_with(flags, "\u{1F1E6}\u{1F1FA}"));
+        assert!(!gc_starts_with(flags, "\u{1F1E6}\u{1F1FA}\u{1F1E6}"));
+        assert!(!gc_ends_with(flags, "\u{1F1FA}"));
+        assert!(!gc_ends_with(flags, "\u{1F1FA}\u{1F1E6}\u{1F1FA}"));
+        assert!(gc_ends_with(flags, "\u{1F1E6}\u{1F1FA}"));
+        assert_eq!(gc_strip_prefix(flags, "\u{1F1E6}\u{1F1FA}"), Some("\u{1F1E6}\u{1F1FA}"));
+    }
+
+    #[test]
+    fn test_truncate_to_graphemes() {
+        let s = format!("ab{}c", FAMILY);
+        assert_eq!(truncate_to_graphemes(&s, 0), "");
+        assert_eq!(truncate_to_graphemes(&s, 2), "ab");
+        assert_eq!(truncate_to_graphemes(&s, 3), format!("ab{}", FAMILY));
+        assert_eq!(truncate_to_graphemes(&s, 4), s);
+        assert_eq!(truncate_to_graphemes(&s, 5), s);
+        assert_eq!(truncate_to_graphemes("", 1), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ellipsize() {
+        let s = format!("ab{}cd", FAMILY);
+        assert_eq!(ellipsize(&s, 5, "…"), s);
+        assert_eq!(ellipsize(&s, 4, "…"), format!("ab{}…", FAMILY));
+        assert_eq!(ellipsize(&s, 3, "…"), "ab…");
+        assert_eq!(ellipsize(&s, 2, "..."), "..");
+        assert_eq!(ellipsize(&s, 0, "…"), "");
+        assert_eq!(ellipsize("", 0, "…"), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ellipsize_bytes() {
+        use std::borrow::Cow;
+        let s = format!("ab{}cdef", FAMILY);
+        assert!(matches!(ellipsize_bytes(&s, s.len(), "…"), Cow::Borrowed(_)));
+        assert_eq!(ellipsize_bytes(&s, 2 + FAMILY.len() + 3, "..."), format!("ab{}...", FAMILY));
+        assert_eq!(ellipsize_bytes(&s, 2 + FAMILY.len() + 2, "..."), "ab...");
+        assert_eq!(ellipsize_bytes(&s, 5, "..."), "ab...");
+        assert_eq!(ellipsize_bytes(&s, 4, "..."), "a...");
+        assert_eq!(ellipsize_bytes(&s, 2, "…"), "");
+        assert_eq!(ellipsize_bytes(&s, 0, "..."), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_reverse_graphemes() {
+        use StrCursor;
+        assert_eq!(reverse_graphemes("noe\u{308}l"), "le\u{308}on");
+        assert_eq!(reverse_graphemes(&format!("a{}b", FAMILY)), format!("b{}a", FAMILY));
+        assert_eq!(reverse_graphemes("a\r\nb"), "b\r\na");
+        assert_eq!(reverse_graphemes(""), "");
+        let s = "Jäger,Jäger,大嫌い,💪❤!";
+        assert_eq!(reverse_graphemes(&reverse_graphemes(s)), s);
+        assert_eq!(StrCursor::new_at_left_of_byte_pos("noe\u{308}l", 5).collect_before_reversed(),
+            "e\u{308}on");
+    }
+}
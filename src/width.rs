@@ -0,0 +1,151 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Display-width aware cursor methods.
+*/
+use StrCursor;
+
+/**
+An iterator over the lines produced by greedily wrapping the text after a cursor to a display width, returned from [`StrCursor::wrap_to_width`](struct.StrCursor.html#method.wrap_to_width).
+
+Requires the `width` feature.
+*/
+#[derive(Clone, Debug)]
+pub struct WrapToWidth<'a> {
+    cur: StrCursor<'a>,
+    width: usize,
+}
+
+impl<'a> WrapToWidth<'a> {
+    /**
+    Returns a cursor at the start of the text which has not yet been wrapped.
+    */
+    pub fn cursor(&self) -> StrCursor<'a> {
+        self.cur
+    }
+}
+
+impl<'a> Iterator for WrapToWidth<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let start = self.cur;
+        let (gc, mut end) = start.next()?;
+        // The first cluster is always taken, even if it is too wide on its own.
+        let mut cols = gc.width();
+        while let Some((gc, next)) = end.next() {
+            cols += gc.width();
+            if cols > self.width {
+                break;
+            }
+            end = next;
+        }
+        self.cur = end;
+        start.slice_between(end)
+    }
+}
+
+impl<'a> StrCursor<'a> {
+    /**
+    Returns an iterator which wraps the text after the cursor into lines no wider than `width` display columns.
+
+    Lines are filled greedily, one grapheme cluster at a time, using [`Gc::width`](struct.Gc.html#method.width); lines are only ever broken between grapheme clusters, never at word boundaries.  A grapheme cluster which is wider than `width` on its own is yielded as a line by itself, rather than being dropped.  Zero-width clusters are kept on the line which precedes them.
+
+    Existing line terminators are *not* treated specially; to wrap each line separately, split the text into lines first.
+
+    Requires the `width` feature.
+    */
+    pub fn wrap_to_width(self, width: usize) -> WrapToWidth<'a> {
+        WrapToWidth {
+            cur: self,
+            width,
+        }
+    }
+
+    /**
+    Returns the 0-based display column of the cursor on its current line.
+
+    This is the rendered width of the line up to the cursor, using [`Gc::width`](struct.Gc.html#method.width) for each grapheme cluster.  Tabs advance to the next multiple of `tab_width` columns; a `tab_width` of zero causes tabs to be ignored.
+
+    Requires the `width` feature.
+    */
+    pub fn display_col(&self, tab_width: usize) -> usize {
+        let mut cur = ::lines::line_start(*self, ::LineTerminators::default());
+        let mut col: usize = 0;
+        while cur.byte_pos() < self.byte_pos() {
+            let (gc, next) = match cur.next() {
+                Some(next) => next,
+                None => break
+            };
+            if gc == "\t" {
+                if let Some(tabs) = col.checked_div(tab_width) {
+                    col = (tabs + 1) * tab_width;
+                }
+            } else {
+                col += gc.width();
+            }
+            cur = next;
+        }
+        col
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use StrCursor;
+
+    fn cols(s: &str, tab_width: usize) -> Vec<usize> {
+        let mut r = vec![];
+        let mut cur = Some(StrCursor::new_at_start(s));
+        while let Some(c) = cur {
+            r.push(c.display_col(tab_width));
+            cur = c.at_next();
+        }
+        r
+    }
+
+    #[test]
+    fn test_display_col() {
+        assert_eq!(cols("漢字 e\u{301}!", 4), vec![0, 2, 4, 5, 6, 7]);
+        assert_eq!(cols("a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}b", 4), vec![0, 1, 3, 4]);
+        assert_eq!(cols("a\tbc\td\n\tx", 4), vec![0, 1, 4, 5, 6, 8, 9, 0, 4, 5]);
+        assert_eq!(cols("a\tb", 8), vec![0, 1, 8, 9]);
+        assert_eq!(cols("a\tb", 0), vec![0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_wrap_to_width() {
+        fn wrap(s: &str, width: usize) -> Vec<&str> {
+            StrCursor::new_at_start(s).wrap_to_width(width).collect()
+        }
+
+        assert_eq!(wrap("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(wrap("abcdef", 3), vec!["abc", "def"]);
+        assert_eq!(wrap("", 3), Vec::<&str>::new());
+
+        // Combining marks stay with their base, and wide clusters are never split.
+        assert_eq!(wrap("e\u{301}e\u{301}e\u{301}e\u{301}", 3), vec!["e\u{301}e\u{301}e\u{301}", "e\u{301}"]);
+        assert_eq!(wrap("a漢字b", 3), vec!["a漢", "字b"]);
+        assert_eq!(wrap("漢字漢", 3), vec!["漢", "字", "漢"]);
+
+        // A cluster wider than the line gets a line of its own.
+        assert_eq!(wrap("a\u{1F468}\u{200D}\u{1F469}b", 1), vec!["a", "\u{1F468}\u{200D}\u{1F469}", "b"]);
+        assert_eq!(wrap("ab", 0), vec!["a", "b"]);
+
+        // Zero-width clusters join the preceding line.
+        assert_eq!(wrap("ab\u{200B}cd", 2), vec!["ab\u{200B}", "cd"]);
+
+        let s = "hello world";
+        let mut it = StrCursor::new_at_left_of_byte_pos(s, 6).wrap_to_width(2);
+        assert_eq!(it.next(), Some("wo"));
+        assert_eq!(it.cursor().slice_after(), "rld");
+    }
+}
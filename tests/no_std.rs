@@ -0,0 +1,65 @@
+/*
+Copyright ⓒ 2015, 2016 Daniel Keep.
+
+Licensed under the MIT license (see LICENSE or <http://opensource.org
+/licenses/MIT>) or the Apache License, Version 2.0 (see LICENSE of
+<http://www.apache.org/licenses/LICENSE-2.0>), at your option. All
+files in the project carrying such notice may not be copied, modified,
+or distributed except according to those terms.
+*/
+/*!
+Exercises cursor movement using only `core`, and owned clusters using `alloc`.
+
+Run with `cargo test --no-default-features` to check the crate builds with only `core`, and with `--no-default-features --features alloc` to check it builds without `std`.  The test harness itself still needs `std`, but nothing below uses it.
+*/
+#![no_std]
+
+#[cfg(feature = "alloc")] extern crate alloc;
+#[cfg(test)] extern crate std;
+extern crate strcursor;
+
+use strcursor::{Gc, StrCursor};
+
+#[test]
+fn test_seek() {
+    let s = "noe\u{308}l \u{1F468}\u{200D}\u{1F469}!";
+    let expected = ["n", "o", "e\u{308}", "l", " ", "\u{1F468}\u{200D}\u{1F469}", "!"];
+    let mut cur = StrCursor::new_at_start(s);
+    let mut count = 0;
+    while let Some((gc, next)) = cur.next() {
+        assert_eq!(gc.as_str(), expected[count]);
+        count += 1;
+        cur = next;
+    }
+    assert_eq!(count, expected.len());
+    assert_eq!(cur.byte_pos(), s.len());
+    assert!(cur.iter_before().map(Gc::as_str).eq(expected.iter().rev().cloned()));
+
+    let cur = cur.at_prev().and_then(|c| c.at_prev()).unwrap();
+    assert_eq!(cur.after().map(Gc::as_str), Some("\u{1F468}\u{200D}\u{1F469}"));
+    assert_eq!(cur.slice_before(), "noe\u{308}l ");
+
+    let cur = StrCursor::new_at_left_of_byte_pos(s, 3);
+    assert_eq!(cur.byte_pos(), 2);
+    assert_eq!(cur.before().map(Gc::as_str), Some("o"));
+    assert_eq!(cur.at_next_cp().and_then(|c| c.cp_after()), Some('\u{308}'));
+}
+
+#[test]
+fn test_gc() {
+    let g = Gc::from_str("e\u{308}").unwrap();
+    assert_eq!(g.base_char(), 'e');
+    assert_eq!(g.mark_str(), "\u{308}");
+    assert_eq!(Gc::from_str("ab"), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_owned() {
+    use alloc::string::String;
+    use strcursor::GcBuf;
+    let g = GcBuf::from(Gc::from_str("e\u{308}").unwrap());
+    assert_eq!(g.base_char(), 'e');
+    let s: String = StrCursor::new_at_start("ab").iter_after().collect();
+    assert_eq!(s, "ab");
+}